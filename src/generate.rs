@@ -0,0 +1,18 @@
+use clap;
+use generator::generate_deployment;
+use project::Project;
+
+const DEFAULT_ENVIRONMENT: &str = "production";
+
+pub fn execute<'a>(matches: &clap::ArgMatches<'a>) -> Result<String, String> {
+    if let Some(matches) = matches.subcommand_matches("deployment") {
+        let project = Project::discover()?;
+        let kind = matches.value_of("KIND").unwrap();
+        let environment = matches
+            .value_of("environment")
+            .unwrap_or(DEFAULT_ENVIRONMENT);
+        generate_deployment(&project, kind, environment)
+    } else {
+        Err(String::from("Nothing to generate"))
+    }
+}
@@ -1,10 +1,18 @@
-pub const DIRECTORIES: [&'static str; 19] = [
+pub const DIRECTORIES: [&'static str; 32] = [
     "client",
     "client/assets",
     "client/src",
     "log",
     "migrations",
     "migrations/00000000000001_create_users",
+    "migrations/00000000000002_password_hashes",
+    "migrations/00000000000003_create_password_resets",
+    "migrations/00000000000004_add_confirmed_at",
+    "migrations/00000000000005_create_sessions",
+    "migrations/00000000000006_create_roles",
+    "migrations/00000000000007_create_rate_limits",
+    "migrations/00000000000008_add_lockout",
+    "migrations/00000000000009_add_two_factor",
     "src",
     "src/apis",
     "src/assets",
@@ -12,9 +20,14 @@ pub const DIRECTORIES: [&'static str; 19] = [
     "src/guards",
     "src/libs",
     "src/models",
+    "src/policies",
     "src/view_models",
     "src/views",
+    "src/views/confirmations",
     "src/views/pages",
+    "src/views/password_resets",
+    "src/views/sessions",
+    "src/views/two_factor",
     "src/views/users",
     "tests",
     "tests/libs",
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+use std::io::prelude::*;
+use std::io::{stdin, stdout};
+use std::fs::{read_dir, DirBuilder, File};
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+use tera::{Context, Tera};
+use toml::Value;
+
+pub const MANIFEST_FILE: &str = "hangar-template.toml";
+
+/// A variable declared in `hangar-template.toml`:
+///
+/// ```toml
+/// [[variables]]
+/// name = "company"
+/// prompt = "Company name"
+/// default = "Acme"
+/// pattern = "^[A-Za-z ]+$"
+/// choices = ["Acme", "Initech"]
+/// ```
+pub struct TemplateVariable {
+    pub name: String,
+    pub prompt: String,
+    pub default: Option<String>,
+    pub pattern: Option<Regex>,
+    pub choices: Vec<String>,
+}
+
+/// Per file options declared in `hangar-template.toml`:
+///
+/// ```toml
+/// [[files]]
+/// path = "migrations/pg_extensions"
+/// when = "database == \"postgresql\""
+/// render = true
+/// ```
+///
+/// `path` can be a file or a directory, `when` is a Tera expression evaluated against the context.
+pub struct TemplateFile {
+    pub path: PathBuf,
+    pub when: Option<String>,
+    pub render: bool,
+}
+
+/// A local project template: a directory with a `hangar-template.toml` manifest and a file tree.
+pub struct LocalTemplate {
+    pub root: PathBuf,
+    pub base: bool,
+    pub variables: Vec<TemplateVariable>,
+    pub files: Vec<TemplateFile>,
+}
+
+impl LocalTemplate {
+    pub fn load(root: &Path) -> Result<LocalTemplate, String> {
+        let manifest_path = root.join(MANIFEST_FILE);
+        let mut content = String::new();
+        match File::open(&manifest_path) {
+            Err(_) => return Err(format!("Cannot find {}", manifest_path.display())),
+            Ok(mut file) => if file.read_to_string(&mut content).is_err() {
+                return Err(format!("Cannot read {}", manifest_path.display()));
+            },
+        }
+        let manifest = match content.parse::<Value>() {
+            Err(why) => return Err(format!("Cannot parse {}: {}", manifest_path.display(), why)),
+            Ok(manifest) => manifest,
+        };
+
+        // Render on top of the default Hangar Project unless `base = false`
+        let base = manifest
+            .get("template")
+            .and_then(|template| template.get("base"))
+            .and_then(Value::as_bool)
+            .unwrap_or(true);
+
+        let mut variables = Vec::new();
+        for variable in tables(&manifest, "variables") {
+            variables.push(parse_variable(variable)?);
+        }
+
+        let mut files = Vec::new();
+        for file in tables(&manifest, "files") {
+            files.push(parse_file(file)?);
+        }
+
+        Ok(LocalTemplate {
+            root: root.to_path_buf(),
+            base,
+            variables,
+            files,
+        })
+    }
+
+    /// Resolve every declared variable from `given` (`--var name=value`), prompting for the rest.
+    /// Variables not declared by the template are refused, as they are likely typos.
+    pub fn resolve_variables(
+        &self,
+        given: &BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, String>, String> {
+        let unknown: Vec<&str> = given
+            .keys()
+            .filter(|name| !self.variables.iter().any(|variable| &variable.name == *name))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Unknown template variables: {}",
+                unknown.join(", ")
+            ));
+        }
+
+        let mut resolved = BTreeMap::new();
+        for variable in &self.variables {
+            let value = match given.get(&variable.name) {
+                Some(value) => {
+                    variable.validate(value)?;
+                    value.clone()
+                }
+                None => variable.prompt_value()?,
+            };
+            resolved.insert(variable.name.clone(), value);
+        }
+        Ok(resolved)
+    }
+
+    /// Render the file tree of the template into `destination`.
+    pub fn render(&self, destination: &Path, context: &Context) -> Result<String, String> {
+        let mut sources = Vec::new();
+        collect_files(&self.root, &mut sources)?;
+
+        for source in sources {
+            let relative = source.strip_prefix(&self.root).unwrap().to_path_buf();
+            if relative == Path::new(MANIFEST_FILE) {
+                continue;
+            }
+
+            let options = self.options_for(&relative);
+            if let Some(when) = options.and_then(|file| file.when.as_ref()) {
+                if !evaluate_condition(when, context)? {
+                    continue;
+                }
+            }
+            let render = options.map(|file| file.render).unwrap_or(true);
+
+            let relative_str = relative.to_str().unwrap_or("");
+            let rendered_path = match Tera::one_off(relative_str, context, false) {
+                Ok(rendered_path) => PathBuf::from(rendered_path),
+                Err(_) => return Err(format!("Cannot render path {}", relative.display())),
+            };
+            if !stays_inside(&rendered_path) {
+                return Err(format!(
+                    "Rendered path {} escapes the destination",
+                    rendered_path.display()
+                ));
+            }
+            let target = destination.join(rendered_path);
+            write_template_file(&source, &target, render, context)?;
+        }
+
+        Ok(String::from("Successfully rendered local template."))
+    }
+
+    // The most specific entry of `[[files]]` matching `relative`
+    fn options_for(&self, relative: &Path) -> Option<&TemplateFile> {
+        self.files
+            .iter()
+            .filter(|file| relative.starts_with(&file.path))
+            .max_by_key(|file| file.path.components().count())
+    }
+}
+
+impl TemplateVariable {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        if !self.choices.is_empty() && !self.choices.iter().any(|choice| choice == value) {
+            return Err(format!(
+                "Invalid value for {}, valid value: {}",
+                self.name,
+                self.choices.join(", ")
+            ));
+        }
+        if let Some(ref pattern) = self.pattern {
+            if !pattern.is_match(value) {
+                return Err(format!(
+                    "Invalid value for {}, must match {}",
+                    self.name,
+                    pattern.as_str()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt_value(&self) -> Result<String, String> {
+        loop {
+            match self.default {
+                Some(ref default) => print!("{} [{}]: ", self.prompt, default),
+                None => print!("{}: ", self.prompt),
+            }
+            if stdout().flush().is_err() {
+                return Err(String::from("Cannot write prompt."));
+            }
+
+            let mut input = String::new();
+            match stdin().read_line(&mut input) {
+                Ok(0) | Err(_) => {
+                    return Err(format!("No value for template variable {}", self.name));
+                }
+                Ok(_) => {}
+            }
+
+            let input = input.trim();
+            let value = match (input.is_empty(), self.default.as_ref()) {
+                (true, Some(default)) => default.clone(),
+                (true, None) => continue,
+                (false, _) => input.to_string(),
+            };
+            match self.validate(&value) {
+                Ok(_) => return Ok(value),
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+}
+
+fn tables<'a>(manifest: &'a Value, key: &str) -> Vec<&'a Value> {
+    manifest
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|array| array.iter().filter(|value| value.is_table()).collect())
+        .unwrap_or_default()
+}
+
+fn parse_variable(variable: &Value) -> Result<TemplateVariable, String> {
+    let name = match variable.get("name").and_then(Value::as_str) {
+        Some(name) => name.to_string(),
+        None => return Err(String::from("Template variable without name")),
+    };
+    let prompt = variable
+        .get("prompt")
+        .and_then(Value::as_str)
+        .unwrap_or(&name)
+        .to_string();
+    let default = variable
+        .get("default")
+        .and_then(Value::as_str)
+        .map(String::from);
+    let pattern = match variable.get("pattern").and_then(Value::as_str) {
+        Some(pattern) => match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(_) => return Err(format!("Invalid pattern for template variable {}", name)),
+        },
+        None => None,
+    };
+    let choices = variable
+        .get("choices")
+        .and_then(Value::as_array)
+        .map(|choices| {
+            choices
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(TemplateVariable {
+        name,
+        prompt,
+        default,
+        pattern,
+        choices,
+    })
+}
+
+fn parse_file(file: &Value) -> Result<TemplateFile, String> {
+    let path = match file.get("path").and_then(Value::as_str) {
+        Some(path) => PathBuf::from(path),
+        None => return Err(String::from("Template file without path")),
+    };
+    let when = file.get("when").and_then(Value::as_str).map(String::from);
+    let render = file.get("render").and_then(Value::as_bool).unwrap_or(true);
+    Ok(TemplateFile { path, when, render })
+}
+
+// Only plain names, a variable like `../x` or `/x` could write anywhere
+fn stays_inside(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn evaluate_condition(when: &str, context: &Context) -> Result<bool, String> {
+    let condition = format!("{{% if {} %}}true{{% endif %}}", when);
+    match Tera::one_off(&condition, context, false) {
+        Ok(result) => Ok(result == "true"),
+        Err(_) => Err(format!("Invalid condition: {}", when)),
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = match read_dir(dir) {
+        Err(_) => return Err(format!("Cannot read directory {}", dir.display())),
+        Ok(entries) => entries,
+    };
+    for entry in entries {
+        let path = match entry {
+            Err(_) => return Err(format!("Cannot read directory {}", dir.display())),
+            Ok(entry) => entry.path(),
+        };
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(())
+}
+
+fn write_template_file(
+    source: &Path,
+    target: &Path,
+    render: bool,
+    context: &Context,
+) -> Result<(), String> {
+    let mut content = Vec::new();
+    match File::open(source) {
+        Err(_) => return Err(format!("Cannot open {}", source.display())),
+        Ok(mut file) => if file.read_to_end(&mut content).is_err() {
+            return Err(format!("Cannot read {}", source.display()));
+        },
+    }
+
+    let content = if render {
+        let text = match String::from_utf8(content) {
+            Err(_) => return Err(format!("Cannot render non UTF-8 file {}", source.display())),
+            Ok(text) => text,
+        };
+        match Tera::one_off(&text, context, false) {
+            Err(why) => {
+                println!("{:?}", why);
+                return Err(format!("Cannot render template {}", source.display()));
+            }
+            Ok(rendered) => rendered.into_bytes(),
+        }
+    } else {
+        content
+    };
+
+    if let Some(parent) = target.parent() {
+        if DirBuilder::new().recursive(true).create(parent).is_err() {
+            return Err(String::from("Cannot create directory."));
+        }
+    }
+    let mut file = match File::create(target) {
+        Err(why) => {
+            println!("{:?}", why);
+            return Err(String::from("Cannot create template."));
+        }
+        Ok(file) => file,
+    };
+    match file.write_all(&content) {
+        Err(why) => {
+            println!("{:?}", why);
+            Err(String::from("Cannot write template."))
+        }
+        Ok(_) => {
+            println!("Generating {}", target.display());
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,7 @@
+mod deployment;
 mod directories;
+mod local_template;
+mod secret;
 mod templates_list;
 mod templates;
 
@@ -10,20 +13,47 @@ use std::collections::hash_map::Iter;
 use new::Setting;
 use self::templates_list::*;
 use self::directories::DIRECTORIES;
+use self::local_template::LocalTemplate;
+use self::secret::generate_secret;
 use tera::Tera;
 use tera::Context;
 
+pub use self::deployment::generate_deployment;
+
 pub fn generate(setting: &Setting) -> Result<String, String> {
+    let local_template = match setting.template {
+        Some(ref template_path) => Some(LocalTemplate::load(template_path)?),
+        None => None,
+    };
+
+    let parsed_context = generate_context(setting);
+    if parsed_context.is_err() {
+        return Err(String::from("Cannot render template."));
+    }
+    let mut context = parsed_context.unwrap();
+
+    if let Some(ref template) = local_template {
+        let variables = template.resolve_variables(&setting.template_variables)?;
+        for (variable_name, variable_value) in &variables {
+            context.add(variable_name, variable_value);
+        }
+    }
+
     println!("Generating Hangar Project {}", setting.name);
-    create_project_directories(setting);
-    let success = create_main_templates(setting);
-    if success.is_err() {
-        return Err(String::from("Failed generating Hangar Project."));
+    let with_base = local_template.as_ref().map(|t| t.base).unwrap_or(true);
+    if with_base {
+        create_project_directories(setting);
+        let success = create_main_templates(setting, &context);
+        if success.is_err() {
+            return Err(String::from("Failed generating Hangar Project."));
+        }
+    } else {
+        create_dir(Path::new(&setting.name))
+            .map_err(|e| format!("Cannot create directory {}: {}", setting.name, e))?;
     }
 
-    let success = create_var_templates(setting);
-    if success.is_err() {
-        return Err(String::from("Failed generating Hangar Project."));
+    if let Some(ref template) = local_template {
+        template.render(Path::new(&setting.name), &context)?;
     }
 
     // Check commands (diesel & git & npm)
@@ -41,7 +71,7 @@ Start the application with `cargo run`."
 
 fn create_project_directories(setting: &Setting) {
     let folder_path = Path::new(&setting.name);
-    create_dir(&folder_path).unwrap();
+    create_dir(folder_path).unwrap();
     for dir in DIRECTORIES.iter() {
         let mut path = PathBuf::new();
         path.push(&setting.name);
@@ -58,78 +88,42 @@ fn create_project_directories(setting: &Setting) {
     }
 }
 
-fn create_main_templates(setting: &Setting) -> Result<String, String> {
-    let main_templates = write_to_file(MAIN_TEMPLATES.iter(), setting);
-    if main_templates.is_err() {
-        return main_templates;
-    }
+fn create_main_templates(setting: &Setting, context: &Context) -> Result<String, String> {
+    render_to_file(MAIN_TEMPLATES.iter(), setting, context)?;
 
-    let db_templates = match setting.db.as_ref() {
-        "mysql" => write_to_file(MYSQL_TEMPLATES.iter(), setting),
-        "sqlite" => write_to_file(SQLITE_TEMPLATES.iter(), setting),
-        _ => write_to_file(POSTGRESQL_TEMPLATES.iter(), setting),
+    match setting.db.as_ref() {
+        "mysql" => render_to_file(MYSQL_TEMPLATES.iter(), setting, context)?,
+        "sqlite" => render_to_file(SQLITE_TEMPLATES.iter(), setting, context)?,
+        _ => render_to_file(POSTGRESQL_TEMPLATES.iter(), setting, context)?,
     };
 
-    if db_templates.is_err() {
-        return db_templates;
-    }
-
     Ok(String::from("Successfully rendered main template."))
 }
 
-fn write_to_file(
+/// Every template is rendered with Tera using the context from `generate_context`.
+/// Templates which must keep their own Tera syntax, like the `.html.tera` views,
+/// wrap it in `{% raw %}...{% endraw %}`.
+fn render_to_file(
     iter: Iter<&'static str, &'static str>,
     setting: &Setting,
+    context: &Context,
 ) -> Result<String, String> {
-    for (main_template_name, main_template_value) in iter {
+    for (template_name, template_value) in iter {
         let mut path = PathBuf::new();
         path.push(&setting.name);
-        path.push(main_template_name);
+        path.push(template_name);
         let file_path = path.as_path();
         let display = file_path.display();
 
-        let mut file = match File::create(&file_path) {
+        let rendered = match Tera::one_off(template_value, context, false) {
             Err(why) => {
                 println!("{:?}", why);
-                return Err(String::from("Cannot create template."));
+                return Err(String::from("Cannot render template."));
             }
-            Ok(file) => file,
+            Ok(rendered) => rendered,
         };
 
-        match file.write_all(main_template_value.as_bytes()) {
-            Err(why) => {
-                println!("{:?}", why);
-                return Err(String::from("Cannot write template."));
-            }
-            Ok(_) => println!("Generating {}", display),
-        }
-    }
-    Ok(String::from("Successfully rendered main template."))
-}
-
-fn create_var_templates(setting: &Setting) -> Result<String, String> {
-    let parsed_context = generate_context(setting);
-    if parsed_context.is_err() {
-        return Err(String::from("Cannot render template."));
-    }
-    let context = parsed_context.unwrap();
-
-    for (var_template_name, var_template_value) in VAR_TEMPLATES.iter() {
-        let mut path = PathBuf::new();
-        path.push(&setting.name);
-        path.push(var_template_name);
-        let file_path = path.as_path();
-        let display = file_path.display();
-
-        let mut tera = Tera::default();
-        tera.add_raw_template(var_template_name, var_template_value);
-        let rendered = tera.render(var_template_name, &context);
-
-        if rendered.is_err() {
-            return Err(String::from("Cannot render template."));
-        }
-
-        let mut file = match File::create(&file_path) {
+        let mut file = match File::create(file_path) {
             Err(why) => {
                 println!("{:?}", why);
                 return Err(String::from("Cannot create template."));
@@ -137,7 +131,7 @@ fn create_var_templates(setting: &Setting) -> Result<String, String> {
             Ok(file) => file,
         };
 
-        match file.write_all(rendered.unwrap().as_bytes()) {
+        match file.write_all(rendered.as_bytes()) {
             Err(why) => {
                 println!("{:?}", why);
                 return Err(String::from("Cannot write template."));
@@ -145,13 +139,22 @@ fn create_var_templates(setting: &Setting) -> Result<String, String> {
             Ok(_) => println!("Generating {}", display),
         }
     }
-
-    Ok(String::from("Successfully rendered var template."))
+    Ok(String::from("Successfully rendered template."))
 }
 
 fn generate_context(setting: &Setting) -> Result<Context, String> {
     let mut context = Context::new();
+    context.add("name", &setting.name);
+    context.add("database", &setting.db);
     context.add("database_url", &setting.db_url);
+    context.add("database_url_staging", &setting.db_url_staging);
+    context.add("database_url_production", &setting.db_url_production);
+    context.add("secret_key_staging", &generate_secret(32));
+    context.add("secret_key_production", &generate_secret(32));
+    context.add("password_salt_staging", &generate_secret(16));
+    context.add("password_salt_production", &generate_secret(16));
+    context.add("token_secret_staging", &generate_secret(32));
+    context.add("token_secret_production", &generate_secret(32));
     context.add("authors_name_email", &setting.author);
     let cargo_db = match setting.db.as_ref() {
         "mysql" => "diesel_mysql",
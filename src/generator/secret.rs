@@ -0,0 +1,95 @@
+use std::collections::hash_map::RandomState;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BASE64_CHARS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Generate a base64 encoded random key, the same shape as `openssl rand -base64 <len>`.
+pub fn generate_secret(len: usize) -> String {
+    encode_base64(&random_bytes(len))
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    let from_urandom = File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut bytes));
+    if from_urandom.is_ok() {
+        return bytes;
+    }
+
+    // No `/dev/urandom`, fallback to randomly seeded hasher from std
+    let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => u64::from(duration.subsec_nanos()) ^ duration.as_secs(),
+        Err(_) => 0,
+    };
+    for (index, chunk) in bytes.chunks_mut(8).enumerate() {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(nanos);
+        hasher.write_usize(index);
+        let value = hasher.finish();
+        for (position, byte) in chunk.iter_mut().enumerate() {
+            *byte = (value >> (position * 8)) as u8;
+        }
+    }
+    bytes
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let first = chunk[0];
+        let second = if chunk.len() > 1 { chunk[1] } else { 0 };
+        let third = if chunk.len() > 2 { chunk[2] } else { 0 };
+        let triple = (u32::from(first) << 16) | (u32::from(second) << 8) | u32::from(third);
+
+        encoded.push(BASE64_CHARS[(triple >> 18) as usize & 63] as char);
+        encoded.push(BASE64_CHARS[(triple >> 12) as usize & 63] as char);
+        if chunk.len() > 1 {
+            encoded.push(BASE64_CHARS[(triple >> 6) as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+        if chunk.len() > 2 {
+            encoded.push(BASE64_CHARS[triple as usize & 63] as char);
+        } else {
+            encoded.push('=');
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_base64, generate_secret};
+
+    #[test]
+    fn encode_base64_matches_rfc_4648_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for &(bytes, encoded) in vectors.iter() {
+            assert_eq!(encode_base64(bytes.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn encode_base64_uses_the_whole_alphabet() {
+        assert_eq!(encode_base64(&[0xfb, 0xff, 0xbf]), "+/+/");
+        assert_eq!(encode_base64(&[0, 0, 0]), "AAAA");
+    }
+
+    #[test]
+    fn generate_secret_has_the_length_of_openssl() {
+        assert_eq!(generate_secret(16).len(), 24);
+        assert_eq!(generate_secret(32).len(), 44);
+        assert!(generate_secret(32).ends_with('='));
+    }
+}
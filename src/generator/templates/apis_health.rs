@@ -0,0 +1,137 @@
+pub static TEXT: &'static str = "use std::fs;
+use std::time::Duration;
+
+use diesel::connection::SimpleConnection;
+use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
+
+use rocket::State;
+#[cfg(feature = \"email\")]
+use rocket::config::Value;
+
+use handlers::{ok, service_unavailable, APIResponse};
+use libs::db::{DatabasePools, DbConnection, REPLICA};
+#[cfg(feature = \"email\")]
+use libs::email::Mailer;
+use libs::email::MailerConfig;
+use libs::settings::Settings;
+
+/// Longest wait for a pooled connection, probes usually time out after a second or two.
+const DATABASE_CHECK_TIMEOUT_SECONDS: u64 = 1;
+
+/// The process is up and handling requests.
+#[get(\"/live\")]
+pub fn live() -> APIResponse {
+    ok().data(json!({\"status\": \"ok\"}))
+}
+
+/// The database, the mailer and the views can be used, with the status of each check,
+/// 503 when one of them failed.
+///
+/// A failing replica only makes the status `degraded`, `ReadConn` falls back to the primary.
+#[get(\"/ready\")]
+pub fn ready(
+    pool: State<Pool<ConnectionManager<DbConnection>>>,
+    database_pools: State<DatabasePools>,
+    mailer_config: Option<State<MailerConfig>>,
+    settings: State<Settings>,
+) -> APIResponse {
+    let database = check_database(&pool);
+    let replica = database_pools.get(REPLICA).map(check_database);
+    let mailer = check_mailer(mailer_config.as_ref().map(|config| &**config));
+    let templates = check_templates(&settings);
+    let ready = database.is_ok() && mailer.is_ok() && templates.is_ok();
+    let degraded = replica.as_ref().map_or(false, Result::is_err);
+
+    let status = match (ready, degraded) {
+        (false, _) => \"fail\",
+        (true, true) => \"degraded\",
+        (true, false) => \"ok\",
+    };
+    let mut data = json!({
+        \"status\": status,
+        \"checks\": {
+            \"database\": Check::from(database),
+            \"mailer\": Check::from(mailer),
+            \"templates\": Check::from(templates),
+        },
+    });
+    if let Some(replica) = replica {
+        data[\"checks\"][REPLICA] = json!(Check::degraded(replica)).0;
+    }
+    if ready {
+        ok().data(data)
+    } else {
+        service_unavailable().data(data)
+    }
+}
+
+#[derive(Serialize)]
+struct Check {
+    status: &'static str,
+    #[serde(skip_serializing_if = \"Option::is_none\")]
+    message: Option<String>,
+}
+
+impl Check {
+    // For the checks which don't make the application unready
+    fn degraded(result: Result<&'static str, String>) -> Check {
+        match result {
+            Err(message) => Check {
+                status: \"degraded\",
+                message: Some(message),
+            },
+            ok => Check::from(ok),
+        }
+    }
+}
+
+impl From<Result<&'static str, String>> for Check {
+    fn from(result: Result<&'static str, String>) -> Check {
+        match result {
+            Ok(status) => Check {
+                status,
+                message: None,
+            },
+            Err(message) => Check {
+                status: \"fail\",
+                message: Some(message),
+            },
+        }
+    }
+}
+
+fn check_database(pool: &Pool<ConnectionManager<DbConnection>>) -> Result<&'static str, String> {
+    let conn = pool
+        .get_timeout(Duration::from_secs(DATABASE_CHECK_TIMEOUT_SECONDS))
+        .map_err(|e| e.to_string())?;
+    conn.batch_execute(\"SELECT 1\").map_err(|e| e.to_string())?;
+    Ok(\"ok\")
+}
+
+// Building the transport validates its configuration without sending anything
+#[cfg(feature = \"email\")]
+fn check_mailer(mailer_config: Option<&MailerConfig>) -> Result<&'static str, String> {
+    let mailer_config = match mailer_config {
+        Some(mailer_config) => &mailer_config.0,
+        None => return Ok(\"disabled\"),
+    };
+    match mailer_config.get(\"enabled\").and_then(Value::as_bool) {
+        Some(true) => Mailer::new_from_config(mailer_config).map(|_| \"ok\"),
+        _ => Ok(\"disabled\"),
+    }
+}
+
+#[cfg(not(feature = \"email\"))]
+fn check_mailer(_mailer_config: Option<&MailerConfig>) -> Result<&'static str, String> {
+    Ok(\"disabled\")
+}
+
+fn check_templates(settings: &Settings) -> Result<&'static str, String> {
+    let template_dir = &settings.views.template_dir;
+    match fs::read_dir(template_dir) {
+        Ok(_) => Ok(\"ok\"),
+        Err(e) => Err(format!(\"{}: {}\", template_dir.display(), e)),
+    }
+}
+";
@@ -0,0 +1,3 @@
+pub static TEXT: &'static str = "pub mod health_api;
+pub mod tokens_api;
+";
@@ -0,0 +1,100 @@
+pub static TEXT: &'static str = "use diesel::prelude::*;
+
+use rocket::State;
+use rocket_contrib::Json;
+
+use controllers::unlocks_controller::notify_lockout;
+use guards::user::{ApiUser, TokenRequest};
+use handlers::{created, forbidden, no_content, unauthorized, APIResponse};
+use libs::db::ConnPool;
+use libs::email::MailerConfig;
+use libs::rate_limit::{RateLimit, TokenLimit};
+use libs::request_log::RequestLogger;
+use libs::settings::Settings;
+use models::user::User;
+use schema::users::dsl::{email, users};
+
+/// Issue an access token for `{\"email\": ..., \"password\": ...}`,
+/// to be sent as `Authorization: Bearer <access_token>`.
+/// Users with two-factor authentication add their TOTP or recovery code as `\"code\"`.
+#[post(\"/\", data = \"<login>\", format = \"application/json\")]
+pub fn create(
+    _rate_limit: RateLimit<TokenLimit>,
+    login: Json<TokenRequest>,
+    db: ConnPool,
+    settings: State<Settings>,
+    mailer_config: Option<State<MailerConfig>>,
+    logger: RequestLogger,
+) -> APIResponse {
+    let user_query = users
+        .filter(email.eq(&login.email))
+        .first::<User>(&**db)
+        .optional();
+
+    let mut user = match user_query {
+        Ok(Some(user)) => user,
+        Ok(None) => return unauthorized(),
+        Err(e) => return APIResponse::from(e),
+    };
+
+    let security = &settings.application_security;
+    let mailer = mailer_config.as_ref().map(|config| config.inner());
+    if user.is_locked() {
+        return forbidden().message(\"Account locked, check your email to unlock it.\");
+    }
+    if !user.verify_password(&login.password, security) {
+        if let Ok(true) = user.register_failed_login(security, &db) {
+            notify_lockout(&user, security, mailer, &logger);
+        }
+        return unauthorized();
+    }
+    match user.requires_two_factor(security, &db) {
+        Ok(true) if !user.has_two_factor() => {
+            return forbidden().message(\"Two-factor authentication required, set it up first.\");
+        }
+        Ok(true) => {
+            let verified = match login.code {
+                Some(ref code) => user.verify_two_factor_code(code, security, &db),
+                None => return unauthorized().message(\"Two-factor code required.\"),
+            };
+            match verified {
+                Ok(true) => {}
+                Ok(false) => {
+                    if let Ok(true) = user.register_failed_login(security, &db) {
+                        notify_lockout(&user, security, mailer, &logger);
+                    }
+                    return unauthorized().message(\"Invalid two-factor code.\");
+                }
+                Err(e) => return APIResponse::from(e),
+            }
+        }
+        Ok(false) => {}
+        Err(e) => return APIResponse::from(e),
+    }
+    let _ = user.reset_failed_logins(&db);
+    if security.require_email_confirmation && !user.is_confirmed() {
+        return forbidden().message(\"Please confirm your email first.\");
+    }
+    // A failed rehash keeps the old hash, which is still valid
+    let _ = user.rehash_password_if_needed(&login.password, security, &db);
+
+    match user.generate_access_token(&db) {
+        Ok(access_token) => created().data(json!({
+            \"access_token\": access_token,
+            \"token_type\": \"Bearer\",
+            \"expires_in_days\": security.access_token_timeout_days,
+        })),
+        Err(e) => APIResponse::from(e),
+    }
+}
+
+/// Revoke the access token of the request.
+#[delete(\"/\")]
+pub fn delete(api_user: ApiUser, db: ConnPool) -> APIResponse {
+    let mut user = api_user.into_user();
+    match user.revoke_access_token(&db) {
+        Ok(_) => no_content(),
+        Err(e) => APIResponse::from(e),
+    }
+}
+";
@@ -46,6 +46,7 @@ serde_derive = \"1\"
 
 uuid = { version = \"0.6.1\", features = [\"use_std\", \"serde\", \"v4\"] }
 chrono = { version = \"0.4\", features = [\"serde\"] }
+dotenv = \"0.13\"
 
 # password encryption + self salting
 argon2rs = \"0.2\"
@@ -55,12 +56,16 @@ validator_derive = \"0.6\"
 cookie = \"0.10.1\"
 ring = \"0.13.0-alpha\"
 rand = \"0.4\"
+qrcode = { version = \"0.7\", default-features = false, features = [\"svg\"] }
 clippy = {version = \"*\", optional = true}
 tera = \"0.11\"
 
 # Logging
 slog = \"2.1.1\"
 sloggers = \"0.2\"
+slog-json = \"2.2\"
+slog-term = \"2.3\"
+flate2 = \"1.0\"
 
 # Error handling
 error-chain = \"0.11.0\"
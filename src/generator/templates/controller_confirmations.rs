@@ -0,0 +1,94 @@
+pub static TEXT: &'static str = "use diesel::prelude::*;
+
+use rocket::request::LenientForm;
+use rocket::response::{Flash, Redirect};
+use rocket::State;
+use rocket_contrib::Template;
+
+use guards::user::ConfirmationRequest;
+use libs::csrf::CsrfToken;
+use libs::db::ConnPool;
+use libs::email::{mailer_base_url, send_text_email, MailerConfig};
+use libs::settings::{ApplicationSecurity, Settings};
+use models::user::User;
+use schema::users::dsl::{email, users};
+use view_models::confirmations::ConfirmationNew;
+use view_models::*;
+
+#[get(\"/new\", format = \"text/html\")]
+pub fn new(csrf_token: CsrfToken) -> Template {
+    render_new(&csrf_token)
+}
+
+#[post(\"/create\", data = \"<request>\", format = \"application/x-www-form-urlencoded\")]
+pub fn create(
+    request: LenientForm<ConfirmationRequest>,
+    db: ConnPool,
+    settings: State<Settings>,
+    mailer_config: Option<State<MailerConfig>>,
+) -> Flash<Redirect> {
+    let request_data = request.get();
+
+    let user_query = users
+        .filter(email.eq(&request_data.email))
+        .first::<User>(&**db)
+        .optional();
+
+    if let (Ok(Some(user)), Some(mailer_config)) = (user_query, mailer_config) {
+        if !user.is_confirmed() {
+            let _ = send_confirmation_email(&user, &settings.application_security, &mailer_config);
+        }
+    }
+
+    // Same answer for unknown or confirmed emails, so registered emails can't be probed
+    Flash::success(
+        Redirect::to(\"/users/signin\"),
+        \"If the email is waiting for confirmation, confirmation instructions have been sent.\",
+    )
+}
+
+#[get(\"/<token>\", format = \"text/html\")]
+pub fn confirm(token: String, db: ConnPool, settings: State<Settings>) -> Flash<Redirect> {
+    let security = &settings.application_security;
+    if let Ok(Some(mut user)) = User::from_confirmation_token(&token, security, &db) {
+        if user.confirm(&db).is_ok() {
+            return Flash::success(
+                Redirect::to(\"/users/signin\"),
+                \"Successfully confirm email.\",
+            );
+        }
+    }
+    Flash::error(
+        Redirect::to(\"/confirmations/new\"),
+        \"Invalid or expired confirmation link.\",
+    )
+}
+
+/// Email `user` a link to `confirm`, valid for `email_confirmation_timeout_hours`.
+pub fn send_confirmation_email(
+    user: &User,
+    security: &ApplicationSecurity,
+    mailer_config: &MailerConfig,
+) -> Result<String, String> {
+    let link = format!(
+        \"{}/confirmations/{}\",
+        mailer_base_url(&mailer_config.0),
+        user.confirmation_token(security)
+    );
+    let body = format!(
+        \"Welcome! Confirm your email here: {}\\n\\n\\
+         If you didn't create an account, ignore this email.\",
+        link
+    );
+    send_text_email(mailer_config, &user.email, \"Confirm your email\", &body)
+}
+
+fn render_new(csrf_token: &CsrfToken) -> Template {
+    let header = DefaultHeader {
+        title: String::from(\"Resend Confirmation\"),
+    };
+    let confirmation_new = ConfirmationNew {};
+    let context = ViewModel::new(&header, &confirmation_new).csrf_token(csrf_token);
+    Template::render(\"confirmations/new\", &context)
+}
+";
@@ -0,0 +1,17 @@
+pub static TEXT: &'static str = "use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
+use rocket::response::content;
+use rocket::State;
+use libs::db::{DatabasePools, DbConnection};
+use libs::metrics::{Metrics, MetricsAccess};
+
+#[get(\"/\")]
+pub fn metrics(
+    _access: MetricsAccess,
+    metrics: State<Metrics>,
+    pool: State<Pool<ConnectionManager<DbConnection>>>,
+    database_pools: State<DatabasePools>,
+) -> content::Plain<String> {
+    content::Plain(metrics.render(&pool, &database_pools))
+}
+";
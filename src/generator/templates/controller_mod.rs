@@ -1,3 +1,9 @@
 pub static TEXT: &'static str = "pub mod assets_controller;
+pub mod confirmations_controller;
+pub mod metrics_controller;
 pub mod pages_controller;
+pub mod password_resets_controller;
+pub mod sessions_controller;
+pub mod two_factor_controller;
+pub mod unlocks_controller;
 pub mod users_controller;";
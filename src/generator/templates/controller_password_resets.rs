@@ -0,0 +1,152 @@
+pub static TEXT: &'static str = "use diesel::prelude::*;
+use diesel::result::Error;
+use validator::Validate;
+
+use rocket::request::LenientForm;
+use rocket::response::{Flash, Redirect};
+use rocket::State;
+use rocket_contrib::Template;
+
+use guards::password_reset::{PasswordResetRequest, PasswordResetUpdate};
+use libs::csrf::CsrfToken;
+use libs::db::ConnPool;
+use libs::email::{mailer_base_url, send_text_email, MailerConfig};
+use libs::settings::Settings;
+use models::password_reset::PasswordReset;
+use models::session::Session;
+use models::user::User;
+use schema::users::dsl::{email, users};
+use view_models::password_resets::{PasswordResetEdit, PasswordResetNew};
+use view_models::*;
+
+#[get(\"/new\", format = \"text/html\")]
+pub fn new(csrf_token: CsrfToken) -> Template {
+    render_new(&csrf_token)
+}
+
+#[post(\"/create\", data = \"<request>\", format = \"application/x-www-form-urlencoded\")]
+pub fn create(
+    request: LenientForm<PasswordResetRequest>,
+    db: ConnPool,
+    settings: State<Settings>,
+    mailer_config: Option<State<MailerConfig>>,
+) -> Flash<Redirect> {
+    let request_data = request.get();
+
+    let user_query = users
+        .filter(email.eq(&request_data.email))
+        .first::<User>(&**db)
+        .optional();
+
+    if let (Ok(Some(user)), Some(mailer_config)) = (user_query, mailer_config) {
+        let valid_for = settings.application_security.password_reset_duration();
+        if let Ok(token) = PasswordReset::create_for_user(&user, valid_for, &db) {
+            let link = format!(
+                \"{}/password_resets/edit/{}\",
+                mailer_base_url(&mailer_config.0),
+                token
+            );
+            let body = format!(
+                \"Someone requested a password reset for your account.\\n\\n\\
+                 Reset your password here: {}\\n\\n\\
+                 If it wasn't you, ignore this email.\",
+                link
+            );
+            let _ = send_text_email(&mailer_config, &user.email, \"Reset your password\", &body);
+        }
+    }
+
+    // Same answer for unknown emails, so registered emails can't be probed
+    Flash::success(
+        Redirect::to(\"/users/signin\"),
+        \"If the email is registered, password reset instructions have been sent.\",
+    )
+}
+
+#[get(\"/edit/<token>\", format = \"text/html\")]
+pub fn edit(
+    token: String,
+    db: ConnPool,
+    csrf_token: CsrfToken,
+) -> Result<Template, Flash<Redirect>> {
+    match PasswordReset::find_valid(&token, &db) {
+        Ok(Some(_)) => Ok(render_edit(token, &csrf_token)),
+        _ => Err(invalid_password_reset()),
+    }
+}
+
+#[post(\"/update\", data = \"<reset>\", format = \"application/x-www-form-urlencoded\")]
+pub fn update(
+    reset: LenientForm<PasswordResetUpdate>,
+    db: ConnPool,
+    settings: State<Settings>,
+    csrf_token: CsrfToken,
+) -> Result<Flash<Redirect>, Template> {
+    let reset_data = reset.into_inner();
+    if reset_data.validate().is_err() {
+        return Err(render_edit(reset_data.token, &csrf_token));
+    };
+
+    let password_reset = match PasswordReset::find_valid(&reset_data.token, &db) {
+        Ok(Some(password_reset)) => password_reset,
+        _ => return Ok(invalid_password_reset()),
+    };
+
+    let user_query = users
+        .find(password_reset.user_id)
+        .first::<User>(&**db)
+        .optional();
+
+    let mut user = match user_query {
+        Ok(Some(user)) => user,
+        _ => return Ok(invalid_password_reset()),
+    };
+
+    // The token is claimed first, so the same link can't reset the password twice
+    let security = &settings.application_security;
+    let reset_result = db.transaction::<_, Error, _>(|| {
+        if !password_reset.claim(&db)? {
+            return Ok(false);
+        }
+        user.update_password(&reset_data.password, security, &db)?;
+        password_reset.use_all(&db)?;
+        Session::revoke_all_for_user(user.id, &db)?;
+        user.reset_failed_logins(&db)?;
+        Ok(true)
+    });
+
+    match reset_result {
+        Ok(true) => Ok(Flash::success(
+            Redirect::to(\"/users/signin\"),
+            \"Successfully reset password.\",
+        )),
+        Ok(false) => Ok(invalid_password_reset()),
+        Err(_) => Err(render_edit(reset_data.token, &csrf_token)),
+    }
+}
+
+fn invalid_password_reset() -> Flash<Redirect> {
+    Flash::error(
+        Redirect::to(\"/password_resets/new\"),
+        \"Invalid or expired password reset link.\",
+    )
+}
+
+fn render_new(csrf_token: &CsrfToken) -> Template {
+    let header = DefaultHeader {
+        title: String::from(\"Forgot Password\"),
+    };
+    let password_reset_new = PasswordResetNew {};
+    let context = ViewModel::new(&header, &password_reset_new).csrf_token(csrf_token);
+    Template::render(\"password_resets/new\", &context)
+}
+
+fn render_edit(token: String, csrf_token: &CsrfToken) -> Template {
+    let header = DefaultHeader {
+        title: String::from(\"Reset Password\"),
+    };
+    let password_reset_edit = PasswordResetEdit { token };
+    let context = ViewModel::new(&header, &password_reset_edit).csrf_token(csrf_token);
+    Template::render(\"password_resets/edit\", &context)
+}
+";
@@ -0,0 +1,60 @@
+pub static TEXT: &'static str = "use rocket::response::{Flash, Redirect};
+use rocket::State;
+use rocket_contrib::Template;
+
+use guards::user::CurrentUser;
+use libs::csrf::CsrfToken;
+use libs::db::{ConnPool, ReadConn};
+use libs::settings::Settings;
+use models::session::Session;
+use view_models::sessions::SessionIndex;
+use view_models::*;
+
+#[get(\"/\", format = \"text/html\")]
+pub fn index(
+    current_user: CurrentUser,
+    db: ReadConn,
+    settings: State<Settings>,
+    csrf_token: CsrfToken,
+) -> Result<Template, Flash<Redirect>> {
+    let security = &settings.application_security;
+    match Session::active_for_user(current_user.id(), security, &db) {
+        Ok(sessions) => {
+            let header = DefaultHeader {
+                title: String::from(\"Sessions\"),
+            };
+            let session_index = SessionIndex {
+                current_session_id: current_user.session_id(),
+                sessions,
+            };
+            let context = ViewModel::new(&header, &session_index).csrf_token(&csrf_token);
+            Ok(Template::render(\"sessions/index\", &context))
+        }
+        Err(_) => Err(Flash::error(Redirect::to(\"/\"), \"Cannot load sessions.\")),
+    }
+}
+
+#[get(\"/\", rank = 2, format = \"text/html\")]
+pub fn failed_index() -> Redirect {
+    Redirect::to(\"/users/signin\")
+}
+
+#[post(\"/revoke/<id>\", format = \"application/x-www-form-urlencoded\")]
+pub fn revoke(id: i32, current_user: CurrentUser, db: ConnPool) -> Flash<Redirect> {
+    match Session::revoke_for_user(current_user.id(), id, &db) {
+        Ok(_) => Flash::success(Redirect::to(\"/sessions\"), \"Successfully sign out session.\"),
+        Err(_) => Flash::error(Redirect::to(\"/sessions\"), \"Cannot sign out session.\"),
+    }
+}
+
+#[post(\"/revoke_all\", format = \"application/x-www-form-urlencoded\")]
+pub fn revoke_all(current_user: CurrentUser, db: ConnPool) -> Flash<Redirect> {
+    match Session::revoke_others(current_user.id(), current_user.session_id(), &db) {
+        Ok(_) => Flash::success(
+            Redirect::to(\"/sessions\"),
+            \"Successfully sign out other sessions.\",
+        ),
+        Err(_) => Flash::error(Redirect::to(\"/sessions\"), \"Cannot sign out other sessions.\"),
+    }
+}
+";
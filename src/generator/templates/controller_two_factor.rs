@@ -0,0 +1,225 @@
+pub static TEXT: &'static str = "use rocket::http::{Cookie, Cookies};
+use rocket::request::LenientForm;
+use rocket::response::{Flash, Redirect};
+use rocket::State;
+use rocket_contrib::Template;
+
+use controllers::unlocks_controller::notify_lockout;
+use guards::session::ClientInfo;
+use guards::two_factor::{TwoFactorCode, TwoFactorPending, TwoFactorUser};
+use guards::user::CurrentUser;
+use libs::csrf::CsrfToken;
+use libs::db::ConnPool;
+use libs::email::MailerConfig;
+use libs::lib_const::SESSION_TOKEN_STR;
+use libs::rate_limit::{LoginLimit, RateLimit};
+use libs::request_log::RequestLogger;
+use libs::settings::Settings;
+use libs::totp::{provisioning_uri, qr_code_svg};
+use models::recovery_code::RecoveryCode;
+use models::session::Session;
+use models::user::User;
+use view_models::two_factor::{RecoveryCodes, TwoFactorNew, TwoFactorVerify};
+use view_models::*;
+
+#[get(\"/new\", format = \"text/html\")]
+pub fn new(
+    two_factor_user: TwoFactorUser,
+    db: ConnPool,
+    settings: State<Settings>,
+    csrf_token: CsrfToken,
+) -> Result<Template, Flash<Redirect>> {
+    let mut user = two_factor_user.user;
+    if user.has_two_factor() {
+        let two_factor_new = TwoFactorNew {
+            enabled: true,
+            secret: None,
+            provisioning_uri: None,
+            qr_code_svg: None,
+        };
+        return Ok(render_new(two_factor_new, &csrf_token));
+    }
+
+    let secret = match user.start_two_factor_enrollment(&db) {
+        Ok(secret) => secret,
+        Err(_) => {
+            return Err(Flash::error(
+                Redirect::to(\"/\"),
+                \"Cannot start two-factor authentication.\",
+            ))
+        }
+    };
+    let uri = provisioning_uri(
+        &secret,
+        &user.email,
+        &settings.application_security.two_factor_issuer,
+    );
+    let two_factor_new = TwoFactorNew {
+        enabled: false,
+        qr_code_svg: qr_code_svg(&uri),
+        secret: Some(secret),
+        provisioning_uri: Some(uri),
+    };
+    Ok(render_new(two_factor_new, &csrf_token))
+}
+
+#[get(\"/new\", rank = 2, format = \"text/html\")]
+pub fn failed_new() -> Redirect {
+    Redirect::to(\"/users/signin\")
+}
+
+/// Enable two-factor authentication with the first code of the app and show the recovery codes,
+/// signing in the users who had to enroll to finish their login.
+#[post(\"/enable\", data = \"<code>\", format = \"application/x-www-form-urlencoded\")]
+pub fn enable(
+    two_factor_user: TwoFactorUser,
+    code: LenientForm<TwoFactorCode>,
+    mut cookies: Cookies,
+    client: ClientInfo,
+    db: ConnPool,
+    settings: State<Settings>,
+) -> Result<Template, Flash<Redirect>> {
+    let security = &settings.application_security;
+    let mut user = two_factor_user.user;
+    match user.enable_two_factor(&code.get().code, &db) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(Flash::error(
+                Redirect::to(\"/two_factor/new\"),
+                \"Invalid code, please try again.\",
+            ))
+        }
+        Err(_) => {
+            return Err(Flash::error(
+                Redirect::to(\"/two_factor/new\"),
+                \"Cannot enable two-factor authentication.\",
+            ))
+        }
+    }
+
+    let codes = match RecoveryCode::generate_for_user(user.id, security, &db) {
+        Ok(codes) => codes,
+        Err(_) => {
+            return Err(Flash::error(
+                Redirect::to(\"/two_factor/new\"),
+                \"Cannot generate recovery codes.\",
+            ))
+        }
+    };
+    if !two_factor_user.signed_in {
+        match Session::create_for_user(user.id, &client, &db) {
+            Ok(token) => cookies.add_private(Cookie::new(SESSION_TOKEN_STR, token)),
+            Err(_) => return Err(Flash::error(Redirect::to(\"/users/signin\"), \"Cannot sign in.\")),
+        }
+        cookies.remove_private(TwoFactorPending::removal_cookie());
+    }
+
+    let header = DefaultHeader {
+        title: String::from(\"Recovery codes\"),
+    };
+    let context = ViewModel::new(&header, &RecoveryCodes { codes });
+    Ok(Template::render(\"two_factor/recovery_codes\", &context))
+}
+
+#[get(\"/verify\", format = \"text/html\")]
+pub fn verify(_pending: TwoFactorPending, csrf_token: CsrfToken) -> Template {
+    render_verify(&csrf_token)
+}
+
+#[get(\"/verify\", rank = 2, format = \"text/html\")]
+pub fn failed_verify() -> Redirect {
+    Redirect::to(\"/users/signin\")
+}
+
+/// Second step of the login, with a TOTP or recovery code.
+/// Wrong codes count as failed logins towards the lockout.
+#[post(\"/authenticate\", data = \"<code>\", format = \"application/x-www-form-urlencoded\")]
+pub fn authenticate(
+    _rate_limit: RateLimit<LoginLimit>,
+    pending: TwoFactorPending,
+    code: LenientForm<TwoFactorCode>,
+    mut cookies: Cookies,
+    client: ClientInfo,
+    db: ConnPool,
+    settings: State<Settings>,
+    mailer_config: Option<State<MailerConfig>>,
+    logger: RequestLogger,
+    csrf_token: CsrfToken,
+) -> Result<Flash<Redirect>, Template> {
+    let security = &settings.application_security;
+    let mut user = match User::from_id(pending.user_id, &db) {
+        Ok(Some(user)) => user,
+        _ => return Ok(Flash::error(Redirect::to(\"/users/signin\"), \"Cannot load user.\")),
+    };
+    if user.is_locked() {
+        cookies.remove_private(TwoFactorPending::removal_cookie());
+        return Ok(Flash::error(
+            Redirect::to(\"/users/signin\"),
+            \"Your account is locked, check your email to unlock it.\",
+        ));
+    }
+    if !user.has_two_factor() {
+        return Ok(Flash::error(
+            Redirect::to(\"/two_factor/new\"),
+            \"Please set up two-factor authentication first.\",
+        ));
+    }
+
+    if let Ok(true) = user.verify_two_factor_code(&code.get().code, security, &db) {
+        let _ = user.reset_failed_logins(&db);
+        cookies.remove_private(TwoFactorPending::removal_cookie());
+        match Session::create_for_user(user.id, &client, &db) {
+            Ok(token) => cookies.add_private(Cookie::new(SESSION_TOKEN_STR, token)),
+            Err(_) => return Err(render_verify(&csrf_token)),
+        }
+        Ok(Flash::success(Redirect::to(\"/\"), \"Successfully login.\"))
+    } else {
+        if let Ok(true) = user.register_failed_login(security, &db) {
+            cookies.remove_private(TwoFactorPending::removal_cookie());
+            let mailer = mailer_config.as_ref().map(|config| config.inner());
+            notify_lockout(&user, security, mailer, &logger);
+        }
+        Err(render_verify(&csrf_token))
+    }
+}
+
+#[post(\"/disable\", data = \"<code>\", format = \"application/x-www-form-urlencoded\")]
+pub fn disable(
+    current_user: CurrentUser,
+    code: LenientForm<TwoFactorCode>,
+    db: ConnPool,
+    settings: State<Settings>,
+) -> Flash<Redirect> {
+    let security = &settings.application_security;
+    let mut user = current_user.into_user();
+    match user.verify_two_factor_code(&code.get().code, security, &db) {
+        Ok(true) => match user.disable_two_factor(&db) {
+            Ok(_) => Flash::success(
+                Redirect::to(\"/\"),
+                \"Successfully disable two-factor authentication.\",
+            ),
+            Err(_) => Flash::error(
+                Redirect::to(\"/two_factor/new\"),
+                \"Cannot disable two-factor authentication.\",
+            ),
+        },
+        _ => Flash::error(Redirect::to(\"/two_factor/new\"), \"Invalid code.\"),
+    }
+}
+
+fn render_new(two_factor_new: TwoFactorNew, csrf_token: &CsrfToken) -> Template {
+    let header = DefaultHeader {
+        title: String::from(\"Two-factor authentication\"),
+    };
+    let context = ViewModel::new(&header, &two_factor_new).csrf_token(csrf_token);
+    Template::render(\"two_factor/new\", &context)
+}
+
+fn render_verify(csrf_token: &CsrfToken) -> Template {
+    let header = DefaultHeader {
+        title: String::from(\"Two-factor authentication\"),
+    };
+    let context = ViewModel::new(&header, &TwoFactorVerify {}).csrf_token(csrf_token);
+    Template::render(\"two_factor/verify\", &context)
+}
+";
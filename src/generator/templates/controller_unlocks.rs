@@ -0,0 +1,50 @@
+pub static TEXT: &'static str = "use rocket::response::{Flash, Redirect};
+use rocket::State;
+
+use libs::db::ConnPool;
+use libs::email::{mailer_base_url, send_text_email, MailerConfig};
+use libs::logger::CombinedLogger;
+use libs::settings::{ApplicationSecurity, Settings};
+use models::user::User;
+
+#[get(\"/<token>\", format = \"text/html\")]
+pub fn unlock(token: String, db: ConnPool, settings: State<Settings>) -> Flash<Redirect> {
+    let security = &settings.application_security;
+    if let Ok(Some(mut user)) = User::from_unlock_token(&token, security, &db) {
+        if user.reset_failed_logins(&db).is_ok() {
+            return Flash::success(
+                Redirect::to(\"/users/signin\"),
+                \"Successfully unlock account.\",
+            );
+        }
+    }
+    Flash::error(
+        Redirect::to(\"/users/signin\"),
+        \"Invalid or expired unlock link.\",
+    )
+}
+
+/// Audit a lockout of `user` and email them a link to `unlock` when a mailer is configured.
+pub fn notify_lockout(
+    user: &User,
+    security: &ApplicationSecurity,
+    mailer_config: Option<&MailerConfig>,
+    logger: &CombinedLogger,
+) {
+    logger.audit(&format!(
+        \"account locked: user_id={} email={} locked_until={:?}\",
+        user.id, user.email, user.locked_until
+    ));
+
+    if let (Some(token), Some(mailer_config)) = (user.unlock_token(security), mailer_config) {
+        let link = format!(\"{}/unlocks/{}\", mailer_base_url(&mailer_config.0), token);
+        let body = format!(
+            \"Your account has been locked after too many failed sign in attempts.\\n\\n\\
+             Unlock it here: {}\\n\\n\\
+             If it wasn't you, consider changing your password.\",
+            link
+        );
+        let _ = send_text_email(mailer_config, &user.email, \"Your account is locked\", &body);
+    }
+}
+";
@@ -2,39 +2,48 @@ pub static TEXT: &'static str = "use diesel::prelude::*;
 use diesel::result::{DatabaseErrorKind, Error};
 use validator::Validate;
 
-use rocket::http::{Cookie, Cookies};
-use rocket::request::{Form, LenientForm};
-use rocket::response::{Flash, Redirect};
+use rocket::http::{Cookie, Cookies, Status};
+use rocket::request::LenientForm;
+use rocket::response::{Failure, Flash, Redirect};
 use rocket::State;
 use rocket_contrib::Template;
 
-use guards::user::{CurrentUser, Login, Registration};
-use libs::lib_const::CURRENT_USER_STR;
-use libs::db::ConnPool;
+use controllers::confirmations_controller::send_confirmation_email;
+use controllers::unlocks_controller::notify_lockout;
+use guards::authorization::Actor;
+use guards::session::ClientInfo;
+use guards::two_factor::TwoFactorPending;
+use guards::user::{Login, Registration};
+use libs::csrf::CsrfToken;
+use libs::lib_const::SESSION_TOKEN_STR;
+use libs::db::{ConnPool, ReadConn, WriteConn};
+use libs::email::MailerConfig;
+use libs::rate_limit::{LoginLimit, RateLimit, RegisterLimit};
+use libs::request_log::RequestLogger;
 use libs::settings::Settings;
+use models::session::Session;
 use models::user::{NewUser, User};
+use policies::user_policy::UserPolicy;
+use policies::Policy;
 use schema::users::dsl::{email, users};
 use view_models::users::{Signin, Signup};
 use view_models::*;
 
 // Sample of showing user information using GET
 #[get(\"/<id>\", format = \"text/html\")]
-pub fn show(
-    id: i32,
-    db: ConnPool,
-    _current_user: CurrentUser,
-) -> Result<Template, Flash<Redirect>> {
-    let user_query = users.find(&id).first::<User>(&**db).optional();
+pub fn show(id: i32, db: ReadConn, actor: Actor) -> Result<Template, Failure> {
+    let user = match users.find(&id).first::<User>(&***db).optional() {
+        Ok(Some(user)) => user,
+        Ok(None) => return Err(Failure(Status::NotFound)),
+        Err(_) => return Err(Failure(Status::InternalServerError)),
+    };
+    UserPolicy::authorize_view(&actor, &user)?;
 
-    if let Ok(Some(user)) = user_query {
-        let header = DefaultHeader {
-            title: String::from(\"User\"),
-        };
-        let context = ViewModel::new(&header, &user);
-        Ok(Template::render(\"users/show\", &context))
-    } else {
-        Err(Flash::error(Redirect::to(\"/\"), \"Unauthorized.\"))
-    }
+    let header = DefaultHeader {
+        title: String::from(\"User\"),
+    };
+    let context = ViewModel::new(&header, &user);
+    Ok(Template::render(\"users/show\", &context))
 }
 
 // Sample of showing user information using GET
@@ -44,21 +53,26 @@ pub fn failed_show(_id: i32) -> Redirect {
 }
 
 #[get(\"/signin\", format = \"text/html\")]
-pub fn signin() -> Template {
-    render_signin()
+pub fn signin(csrf_token: CsrfToken) -> Template {
+    render_signin(&csrf_token)
 }
 
 #[get(\"/signup\", format = \"text/html\")]
-pub fn signup() -> Template {
-    render_signup(None)
+pub fn signup(csrf_token: CsrfToken) -> Template {
+    render_signup(None, &csrf_token)
 }
 
 #[post(\"/login\", data = \"<user>\", format = \"application/x-www-form-urlencoded\")]
 pub fn login(
+    _rate_limit: RateLimit<LoginLimit>,
     mut cookies: Cookies,
-    user: Form<Login>,
+    user: LenientForm<Login>,
+    client: ClientInfo,
     db: ConnPool,
     settings: State<Settings>,
+    mailer_config: Option<State<MailerConfig>>,
+    logger: RequestLogger,
+    csrf_token: CsrfToken,
 ) -> Result<Flash<Redirect>, Template> {
     let user_data = user.get();
 
@@ -67,69 +81,127 @@ pub fn login(
         .first::<User>(&**db)
         .optional();
 
-    if let Ok(Some(user_result)) = user_query {
-        let salt = &settings.application_security.password_salt;
-        if !user_result.verify_password(&user_data.password, salt) {
-            return Err(render_signin());
+    if let Ok(Some(mut user_result)) = user_query {
+        let security = &settings.application_security;
+        if user_result.is_locked() {
+            return Ok(Flash::error(
+                Redirect::to(\"/users/signin\"),
+                \"Your account is locked, check your email to unlock it.\",
+            ));
+        }
+        if !user_result.verify_password(&user_data.password, security) {
+            if let Ok(true) = user_result.register_failed_login(security, &db) {
+                let mailer = mailer_config.as_ref().map(|config| config.inner());
+                notify_lockout(&user_result, security, mailer, &logger);
+            }
+            return Err(render_signin(&csrf_token));
         };
-
-        cookies.add_private(Cookie::new(
-            CURRENT_USER_STR,
-            user_result.id.to_string(),
-        ));
+        if security.require_email_confirmation && !user_result.is_confirmed() {
+            return Ok(Flash::error(
+                Redirect::to(\"/confirmations/new\"),
+                \"Please confirm your email first.\",
+            ));
+        }
+        // A failed rehash keeps the old hash, which is still valid
+        let _ = user_result.rehash_password_if_needed(&user_data.password, security, &db);
+
+        // Failed logins are only reset once the second factor is verified too
+        match user_result.requires_two_factor(security, &db) {
+            Ok(true) => {
+                cookies.add_private(TwoFactorPending::cookie(user_result.id));
+                return Ok(if user_result.has_two_factor() {
+                    Flash::success(
+                        Redirect::to(\"/two_factor/verify\"),
+                        \"Please enter your two-factor authentication code.\",
+                    )
+                } else {
+                    Flash::error(
+                        Redirect::to(\"/two_factor/new\"),
+                        \"Your account requires two-factor authentication, please set it up.\",
+                    )
+                });
+            }
+            Ok(false) => {}
+            Err(_) => return Err(render_signin(&csrf_token)),
+        }
+        let _ = user_result.reset_failed_logins(&db);
+
+        match Session::create_for_user(user_result.id, &client, &db) {
+            Ok(token) => cookies.add_private(Cookie::new(SESSION_TOKEN_STR, token)),
+            Err(_) => return Err(render_signin(&csrf_token)),
+        }
         Ok(Flash::success(Redirect::to(\"/\"), \"Successfully login.\"))
     } else {
-        Err(render_signin())
+        Err(render_signin(&csrf_token))
     }
 }
 
 #[get(\"/logout\", format = \"text/html\")]
-pub fn logout(mut cookies: Cookies) -> Flash<Redirect> {
-    cookies.remove_private(Cookie::named(CURRENT_USER_STR));
+pub fn logout(mut cookies: Cookies, db: ConnPool) -> Flash<Redirect> {
+    if let Some(cookie) = cookies.get_private(SESSION_TOKEN_STR) {
+        let _ = Session::revoke_token(cookie.value(), &db);
+    }
+    cookies.remove_private(Cookie::named(SESSION_TOKEN_STR));
     Flash::success(Redirect::to(\"/\"), \"Successfully logout.\")
 }
 
 #[post(\"/register\", data = \"<user>\", format = \"application/x-www-form-urlencoded\")]
 pub fn register(
+    _rate_limit: RateLimit<RegisterLimit>,
     user: LenientForm<Registration>,
-    db: ConnPool,
+    db: WriteConn,
     settings: State<Settings>,
-) -> Result<Flash<Redirect>, Template> {
+    mailer_config: Option<State<MailerConfig>>,
+    csrf_token: CsrfToken,
+) -> Result<Result<Flash<Redirect>, Template>, Failure> {
     let user_data = user.into_inner();
     if user_data.validate().is_err() {
-        return Err(render_signup(user_data.email));
+        return Ok(Err(render_signup(user_data.email, &csrf_token)));
     };
 
-    let salt = &settings.application_security.password_salt;
+    let security = &settings.application_security;
+
+    let new_user = match NewUser::new_from_user_data(&user_data, security, &db) {
+        Ok(new_user) => new_user,
+        Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            return Ok(Err(render_signup(None, &csrf_token)))
+        }
+        Err(_) => return Err(Failure(Status::InternalServerError)),
+    };
 
-    let new_user = NewUser::new_from_user_data(&user_data, salt, &db);
-    if let Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) = new_user {
-        return Err(render_signup(None));
+    if security.require_email_confirmation && !new_user.is_confirmed() {
+        if let Some(mailer_config) = mailer_config {
+            let _ = send_confirmation_email(&new_user, security, &mailer_config);
+        }
+        return Ok(Ok(Flash::success(
+            Redirect::to(\"/users/signin\"),
+            \"Successfully create user, check your email to confirm it.\",
+        )));
     }
 
-    Ok(Flash::success(
+    Ok(Ok(Flash::success(
         Redirect::to(\"/\"),
         \"Successfully create user.\",
-    ))
+    )))
 }
 
-fn render_signup(registration_email: Option<String>) -> Template {
+fn render_signup(registration_email: Option<String>, csrf_token: &CsrfToken) -> Template {
     let header = DefaultHeader {
         title: String::from(\"Signup\"),
     };
     let user_signup = Signup {
         email: registration_email,
     };
-    let context = ViewModel::new(&header, &user_signup);
+    let context = ViewModel::new(&header, &user_signup).csrf_token(csrf_token);
     Template::render(\"users/signup\", &context)
 }
 
-fn render_signin() -> Template {
+fn render_signin(csrf_token: &CsrfToken) -> Template {
     let header = DefaultHeader {
         title: String::from(\"Signin\"),
     };
     let user_signin = Signin {};
-    let context = ViewModel::new(&header, &user_signin);
+    let context = ViewModel::new(&header, &user_signin).csrf_token(csrf_token);
     Template::render(\"users/signin\", &context)
 }
-";
\ No newline at end of file
+";
@@ -57,3 +57,143 @@ SELECT diesel_manage_updated_at('users');
 CREATE UNIQUE INDEX email_idx ON users(email);
 CREATE UNIQUE INDEX access_token_idx ON users(access_token);
 ";
+
+pub static DOWN_02: &'static str = "-- This file should undo anything in `up.sql`
+-- Users registered after `up.sql` only have `password_hash`, they have to be removed first
+ALTER TABLE users DROP COLUMN password_hash;
+ALTER TABLE users ALTER COLUMN encrypted_password SET NOT NULL;
+";
+
+pub static UP_02: &'static str = "-- Per-user salted PHC strings, `encrypted_password` is kept for users not logged in yet
+ALTER TABLE users ALTER COLUMN encrypted_password DROP NOT NULL;
+ALTER TABLE users ADD COLUMN password_hash VARCHAR(255) NULL;
+";
+
+pub static DOWN_03: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE password_resets;
+";
+
+pub static UP_03: &'static str = "-- Your SQL goes here
+CREATE TABLE password_resets (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  token_hash VARCHAR(64) NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP DEFAULT current_timestamp NOT NULL
+);
+
+CREATE UNIQUE INDEX password_resets_token_hash_idx ON password_resets(token_hash);
+CREATE INDEX password_resets_user_id_idx ON password_resets(user_id);
+";
+
+pub static DOWN_04: &'static str = "-- This file should undo anything in `up.sql`
+ALTER TABLE users DROP COLUMN confirmed_at;
+";
+
+pub static UP_04: &'static str = "-- Existing users are considered confirmed
+ALTER TABLE users ADD COLUMN confirmed_at TIMESTAMP NULL;
+UPDATE users SET confirmed_at = created_at;
+";
+
+pub static DOWN_05: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE sessions;
+";
+
+pub static UP_05: &'static str = "-- Your SQL goes here
+CREATE TABLE sessions (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  token_hash VARCHAR(64) NOT NULL,
+  user_agent VARCHAR(255) NULL,
+  ip_address VARCHAR(45) NULL,
+  created_at TIMESTAMP NOT NULL,
+  last_seen_at TIMESTAMP NOT NULL,
+  revoked_at TIMESTAMP NULL
+);
+
+CREATE UNIQUE INDEX sessions_token_hash_idx ON sessions(token_hash);
+CREATE INDEX sessions_user_id_idx ON sessions(user_id);
+";
+
+pub static DOWN_06: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE user_roles;
+DROP TABLE role_permissions;
+DROP TABLE permissions;
+DROP TABLE roles;
+";
+
+pub static UP_06: &'static str = "-- Your SQL goes here
+CREATE TABLE roles (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(64) UNIQUE NOT NULL
+);
+
+CREATE TABLE permissions (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(64) UNIQUE NOT NULL
+);
+
+CREATE TABLE role_permissions (
+  role_id INTEGER NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+  permission_id INTEGER NOT NULL REFERENCES permissions(id) ON DELETE CASCADE,
+  PRIMARY KEY (role_id, permission_id)
+);
+
+CREATE TABLE user_roles (
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  role_id INTEGER NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+  PRIMARY KEY (user_id, role_id)
+);
+
+INSERT INTO roles (name) VALUES ('admin');
+INSERT INTO permissions (name) VALUES ('users.view'), ('users.edit'), ('users.delete');
+INSERT INTO role_permissions (role_id, permission_id)
+  SELECT roles.id, permissions.id FROM roles, permissions WHERE roles.name = 'admin';
+";
+
+pub static DOWN_07: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE rate_limits;
+";
+
+pub static UP_07: &'static str = "-- Your SQL goes here
+CREATE TABLE rate_limits (
+  bucket VARCHAR(128) NOT NULL,
+  hits INTEGER NOT NULL,
+  window_end BIGINT NOT NULL,
+  PRIMARY KEY (bucket)
+);
+";
+
+pub static DOWN_08: &'static str = "-- This file should undo anything in `up.sql`
+ALTER TABLE users DROP COLUMN locked_until;
+ALTER TABLE users DROP COLUMN failed_attempts;
+";
+
+pub static UP_08: &'static str = "-- Failed logins since the last successful one, and lockout
+ALTER TABLE users ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until TIMESTAMP NULL;
+";
+
+pub static DOWN_09: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE recovery_codes;
+ALTER TABLE users DROP COLUMN totp_last_counter;
+ALTER TABLE users DROP COLUMN totp_enabled_at;
+ALTER TABLE users DROP COLUMN totp_secret;
+";
+
+pub static UP_09: &'static str = "-- TOTP secret, set on enrollment and enabled once a first code is verified
+ALTER TABLE users ADD COLUMN totp_secret VARCHAR(64) NULL;
+ALTER TABLE users ADD COLUMN totp_enabled_at TIMESTAMP NULL;
+ALTER TABLE users ADD COLUMN totp_last_counter BIGINT NULL;
+
+CREATE TABLE recovery_codes (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  code_hash VARCHAR(64) NOT NULL,
+  used_at TIMESTAMP NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX recovery_codes_user_id_idx ON recovery_codes(user_id);
+";
@@ -15,3 +15,256 @@ CREATE TABLE users (
 CREATE UNIQUE INDEX email_idx ON users(email);
 CREATE UNIQUE INDEX access_token_idx ON users(access_token);
 ";
+
+pub static DOWN_02: &'static str = "-- This file should undo anything in `up.sql`
+-- Users registered after `up.sql` only have `password_hash`, they have to be removed first:
+-- the migration fails while there are some, instead of dropping them with the table
+CREATE TEMP TABLE users_without_encrypted_password (id int);
+CREATE TEMP TRIGGER users_without_encrypted_password_abort
+  BEFORE INSERT ON users_without_encrypted_password
+BEGIN
+  SELECT RAISE(ABORT, 'Some users only have password_hash, remove them first');
+END;
+INSERT INTO users_without_encrypted_password SELECT id FROM users WHERE encrypted_password IS NULL;
+DROP TABLE users_without_encrypted_password;
+
+CREATE TABLE users_old (
+  id int NOT NULL,
+  email VARCHAR(128) UNIQUE NOT NULL,
+  encrypted_password BLOB NOT NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  access_token varchar(32),
+  last_access TIMESTAMP NOT NULL,
+  PRIMARY KEY (id)
+);
+
+INSERT INTO users_old (id, email, encrypted_password, created_at, updated_at, access_token, last_access)
+  SELECT id, email, encrypted_password, created_at, updated_at, access_token, last_access
+  FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_old RENAME TO users;
+
+CREATE UNIQUE INDEX email_idx ON users(email);
+CREATE UNIQUE INDEX access_token_idx ON users(access_token);
+";
+
+pub static UP_02: &'static str = "-- Per-user salted PHC strings, `encrypted_password` is kept for users not logged in yet
+-- SQLite can't drop NOT NULL, so the table is rebuilt
+CREATE TABLE users_new (
+  id int NOT NULL,
+  email VARCHAR(128) UNIQUE NOT NULL,
+  encrypted_password BLOB,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  access_token varchar(32),
+  last_access TIMESTAMP NOT NULL,
+  password_hash VARCHAR(255),
+  PRIMARY KEY (id)
+);
+
+INSERT INTO users_new (id, email, encrypted_password, created_at, updated_at, access_token, last_access)
+  SELECT id, email, encrypted_password, created_at, updated_at, access_token, last_access
+  FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_new RENAME TO users;
+
+CREATE UNIQUE INDEX email_idx ON users(email);
+CREATE UNIQUE INDEX access_token_idx ON users(access_token);
+";
+
+pub static DOWN_03: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE password_resets;
+";
+
+pub static UP_03: &'static str = "-- Your SQL goes here
+CREATE TABLE password_resets (
+  id INTEGER PRIMARY KEY NOT NULL,
+  user_id int NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  token_hash VARCHAR(64) NOT NULL,
+  expires_at TIMESTAMP NOT NULL,
+  used_at TIMESTAMP,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE UNIQUE INDEX password_resets_token_hash_idx ON password_resets(token_hash);
+CREATE INDEX password_resets_user_id_idx ON password_resets(user_id);
+";
+
+pub static DOWN_04: &'static str = "-- This file should undo anything in `up.sql`
+-- SQLite can't drop a column, so the table is rebuilt
+CREATE TABLE users_old (
+  id int NOT NULL,
+  email VARCHAR(128) UNIQUE NOT NULL,
+  encrypted_password BLOB,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  access_token varchar(32),
+  last_access TIMESTAMP NOT NULL,
+  password_hash VARCHAR(255),
+  PRIMARY KEY (id)
+);
+
+INSERT INTO users_old (id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash)
+  SELECT id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash
+  FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_old RENAME TO users;
+
+CREATE UNIQUE INDEX email_idx ON users(email);
+CREATE UNIQUE INDEX access_token_idx ON users(access_token);
+";
+
+pub static UP_04: &'static str = "-- Existing users are considered confirmed
+ALTER TABLE users ADD COLUMN confirmed_at TIMESTAMP;
+UPDATE users SET confirmed_at = created_at;
+";
+
+pub static DOWN_05: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE sessions;
+";
+
+pub static UP_05: &'static str = "-- Your SQL goes here
+CREATE TABLE sessions (
+  id INTEGER PRIMARY KEY NOT NULL,
+  user_id int NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  token_hash VARCHAR(64) NOT NULL,
+  user_agent VARCHAR(255),
+  ip_address VARCHAR(45),
+  created_at TIMESTAMP NOT NULL,
+  last_seen_at TIMESTAMP NOT NULL,
+  revoked_at TIMESTAMP
+);
+
+CREATE UNIQUE INDEX sessions_token_hash_idx ON sessions(token_hash);
+CREATE INDEX sessions_user_id_idx ON sessions(user_id);
+";
+
+pub static DOWN_06: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE user_roles;
+DROP TABLE role_permissions;
+DROP TABLE permissions;
+DROP TABLE roles;
+";
+
+pub static UP_06: &'static str = "-- Your SQL goes here
+CREATE TABLE roles (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR(64) UNIQUE NOT NULL
+);
+
+CREATE TABLE permissions (
+  id INTEGER PRIMARY KEY NOT NULL,
+  name VARCHAR(64) UNIQUE NOT NULL
+);
+
+CREATE TABLE role_permissions (
+  role_id int NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+  permission_id int NOT NULL REFERENCES permissions(id) ON DELETE CASCADE,
+  PRIMARY KEY (role_id, permission_id)
+);
+
+CREATE TABLE user_roles (
+  user_id int NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  role_id int NOT NULL REFERENCES roles(id) ON DELETE CASCADE,
+  PRIMARY KEY (user_id, role_id)
+);
+
+INSERT INTO roles (name) VALUES ('admin');
+INSERT INTO permissions (name) VALUES ('users.view'), ('users.edit'), ('users.delete');
+INSERT INTO role_permissions (role_id, permission_id)
+  SELECT roles.id, permissions.id FROM roles, permissions WHERE roles.name = 'admin';
+";
+
+pub static DOWN_07: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE rate_limits;
+";
+
+pub static UP_07: &'static str = "-- Your SQL goes here
+CREATE TABLE rate_limits (
+  bucket VARCHAR(128) NOT NULL,
+  hits INTEGER NOT NULL,
+  window_end BIGINT NOT NULL,
+  PRIMARY KEY (bucket)
+);
+";
+
+pub static DOWN_08: &'static str = "-- This file should undo anything in `up.sql`
+-- SQLite can't drop a column, so the table is rebuilt
+CREATE TABLE users_old (
+  id int NOT NULL,
+  email VARCHAR(128) UNIQUE NOT NULL,
+  encrypted_password BLOB,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  access_token varchar(32),
+  last_access TIMESTAMP NOT NULL,
+  password_hash VARCHAR(255),
+  confirmed_at TIMESTAMP,
+  PRIMARY KEY (id)
+);
+
+INSERT INTO users_old (id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash, confirmed_at)
+  SELECT id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash, confirmed_at
+  FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_old RENAME TO users;
+
+CREATE UNIQUE INDEX email_idx ON users(email);
+CREATE UNIQUE INDEX access_token_idx ON users(access_token);
+";
+
+pub static UP_08: &'static str = "-- Failed logins since the last successful one, and lockout
+ALTER TABLE users ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE users ADD COLUMN locked_until TIMESTAMP;
+";
+
+pub static DOWN_09: &'static str = "-- This file should undo anything in `up.sql`
+DROP TABLE recovery_codes;
+
+-- SQLite can't drop a column, so the table is rebuilt
+CREATE TABLE users_old (
+  id int NOT NULL,
+  email VARCHAR(128) UNIQUE NOT NULL,
+  encrypted_password BLOB,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  access_token varchar(32),
+  last_access TIMESTAMP NOT NULL,
+  password_hash VARCHAR(255),
+  confirmed_at TIMESTAMP,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  locked_until TIMESTAMP,
+  PRIMARY KEY (id)
+);
+
+INSERT INTO users_old (id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash, confirmed_at, failed_attempts, locked_until)
+  SELECT id, email, encrypted_password, created_at, updated_at, access_token, last_access, password_hash, confirmed_at, failed_attempts, locked_until
+  FROM users;
+
+DROP TABLE users;
+ALTER TABLE users_old RENAME TO users;
+
+CREATE UNIQUE INDEX email_idx ON users(email);
+CREATE UNIQUE INDEX access_token_idx ON users(access_token);
+";
+
+pub static UP_09: &'static str = "-- TOTP secret, set on enrollment and enabled once a first code is verified
+ALTER TABLE users ADD COLUMN totp_secret VARCHAR(64);
+ALTER TABLE users ADD COLUMN totp_enabled_at TIMESTAMP;
+ALTER TABLE users ADD COLUMN totp_last_counter BIGINT;
+
+CREATE TABLE recovery_codes (
+  id INTEGER PRIMARY KEY NOT NULL,
+  user_id int NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  code_hash VARCHAR(64) NOT NULL,
+  used_at TIMESTAMP,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE INDEX recovery_codes_user_id_idx ON recovery_codes(user_id);
+";
@@ -0,0 +1,49 @@
+pub static TEXT: &'static str = "version: \"3\"
+
+services:
+  app:
+    build: .
+    image: {{name}}:{{version}}
+    restart: unless-stopped
+    ports:
+      - \"{{port}}:{{port}}\"
+    environment:
+      ROCKET_ENV: {{environment}}
+      ROCKET_ADDRESS: 0.0.0.0
+      ROCKET_PORT: {{port}}
+      ROCKET_DATABASE_URL: \"{{database_url}}\"
+{%- if db != \"sqlite\" %}
+    depends_on:
+      - db
+{%- else %}
+    volumes:
+      - db_data:/opt/{{name}}/db
+{%- endif %}
+{%- if db == \"postgresql\" %}
+
+  db:
+    image: postgres:10
+    restart: unless-stopped
+    environment:
+      POSTGRES_USER: {{database_user}}
+      POSTGRES_PASSWORD: {{database_password}}
+      POSTGRES_DB: {{database_name}}
+    volumes:
+      - db_data:/var/lib/postgresql/data
+{%- elif db == \"mysql\" %}
+
+  db:
+    image: mysql:5.7
+    restart: unless-stopped
+    environment:
+      MYSQL_USER: {{database_user}}
+      MYSQL_PASSWORD: {{database_password}}
+      MYSQL_ROOT_PASSWORD: {{database_password}}
+      MYSQL_DATABASE: {{database_name}}
+    volumes:
+      - db_data:/var/lib/mysql
+{%- endif %}
+
+volumes:
+  db_data:
+";
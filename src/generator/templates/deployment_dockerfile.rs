@@ -0,0 +1,28 @@
+pub static TEXT: &'static str = "# Build stage
+FROM rustlang/rust:nightly AS builder
+
+WORKDIR /usr/src/{{name}}
+COPY . .
+RUN cargo build --release --bin {{bin}}
+
+# Runtime stage
+FROM debian:stretch-slim
+
+RUN apt-get update \\
+    && apt-get install -y --no-install-recommends ca-certificates {{runtime_packages}} \\
+    && rm -rf /var/lib/apt/lists/*
+
+WORKDIR /opt/{{name}}
+COPY --from=builder /usr/src/{{name}}/target/release/{{bin}} ./{{bin}}
+COPY --from=builder /usr/src/{{name}}/src/views ./src/views
+COPY --from=builder /usr/src/{{name}}/src/assets ./src/assets
+COPY --from=builder /usr/src/{{name}}/Rocket.toml ./Rocket.toml
+RUN mkdir -p log
+
+ENV ROCKET_ENV={{environment}}
+ENV ROCKET_ADDRESS=0.0.0.0
+ENV ROCKET_PORT={{port}}
+EXPOSE {{port}}
+
+CMD [\"./{{bin}}\"]
+";
@@ -0,0 +1,18 @@
+pub static TEXT: &'static str = "[Unit]
+Description={{name}} web application
+After=network.target{% if db == \"postgresql\" %} postgresql.service{% elif db == \"mysql\" %} mysql.service{% endif %}
+
+[Service]
+Type=simple
+User={{name}}
+Group={{name}}
+WorkingDirectory=/opt/{{name}}
+Environment=ROCKET_ENV={{environment}}
+Environment=ROCKET_PORT={{port}}
+ExecStart=/opt/{{name}}/{{bin}}
+Restart=on-failure
+RestartSec=5
+
+[Install]
+WantedBy=multi-user.target
+";
@@ -1,2 +1,5 @@
-pub static TEXT: &'static str = "export ROCKET_ENV=development
+pub static TEXT: &'static str = "# Loaded by diesel-cli and by the app at startup, `DATABASE_URL` overrides `database_url` of `Rocket.toml`
+# Any setting can be overridden with `HANGAR_<KEY>`, nested ones with `HANGAR_<TABLE>__<KEY>`,
+# e.g. `HANGAR_MAILER__SMTP_PASSWORD`, and read from a file with `HANGAR_<KEY>_FILE`
+export ROCKET_ENV=development
 export DATABASE_URL={{database_url}}";
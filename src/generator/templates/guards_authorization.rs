@@ -0,0 +1,90 @@
+pub static TEXT: &'static str = "use std::marker::PhantomData;
+
+use rocket::http::Status;
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request};
+
+use guards::user::CurrentUser;
+use libs::db::ConnPool;
+use models::role::Role as RoleRecord;
+
+/// The signed in user with their roles and permissions, given to the policies.
+///
+/// Forwards when nobody is signed in, like `CurrentUser`.
+#[derive(Debug)]
+pub struct Actor {
+    pub user_id: i32,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Actor {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Actor, ()> {
+        let current_user = request.guard::<CurrentUser>()?;
+        let db = request.guard::<ConnPool>()?;
+
+        let user_id = current_user.id();
+        match (
+            RoleRecord::names_for_user(user_id, &db),
+            RoleRecord::permissions_for_user(user_id, &db),
+        ) {
+            (Ok(roles), Ok(permissions)) => Outcome::Success(Actor {
+                user_id,
+                roles,
+                permissions,
+            }),
+            _ => Outcome::Failure((Status::ServiceUnavailable, ())),
+        }
+    }
+}
+
+impl Actor {
+    pub fn has_role(&self, name: &str) -> bool {
+        self.roles.iter().any(|role| role == name)
+    }
+
+    pub fn has_permission(&self, name: &str) -> bool {
+        self.permissions.iter().any(|permission| permission == name)
+    }
+}
+
+/// A role from the `roles` table, used as `Require<Admin>`.
+pub trait Role {
+    const NAME: &'static str;
+}
+
+pub struct Admin;
+
+impl Role for Admin {
+    const NAME: &'static str = \"admin\";
+}
+
+/// Only lets in users with the role `R`, others get the 403 catcher:
+///
+/// ```rust,ignore
+/// #[get(\"/admin\")]
+/// pub fn admin(_admin: Require<Admin>) -> &'static str { ... }
+/// ```
+pub struct Require<R: Role> {
+    pub actor: Actor,
+    role: PhantomData<R>,
+}
+
+impl<'a, 'r, R: Role> FromRequest<'a, 'r> for Require<R> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Require<R>, ()> {
+        let actor = request.guard::<Actor>()?;
+        if actor.has_role(R::NAME) {
+            Outcome::Success(Require {
+                actor,
+                role: PhantomData,
+            })
+        } else {
+            Outcome::Failure((Status::Forbidden, ()))
+        }
+    }
+}
+";
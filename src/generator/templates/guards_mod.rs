@@ -1,3 +1,7 @@
-pub static TEXT: &'static str = "pub mod sanitized_str;
+pub static TEXT: &'static str = "pub mod authorization;
+pub mod password_reset;
+pub mod sanitized_str;
+pub mod session;
+pub mod two_factor;
 pub mod user;
 ";
@@ -0,0 +1,16 @@
+pub static TEXT: &'static str = "use validator::Validate;
+
+#[derive(Serialize, Deserialize, FromForm)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Validate, Serialize, Deserialize, FromForm)]
+pub struct PasswordResetUpdate {
+    pub token: String,
+    #[validate(length(min = \"8\"))]
+    pub password: String,
+    #[validate(must_match = \"password\")]
+    pub password_confirmation: String,
+}
+";
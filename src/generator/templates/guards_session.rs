@@ -0,0 +1,28 @@
+pub static TEXT: &'static str = "use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request};
+
+const USER_AGENT_MAX_LENGTH: usize = 255;
+
+/// Device and address of the client, stored with its sessions.
+#[derive(Debug)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientInfo {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<ClientInfo, ()> {
+        let user_agent = request
+            .headers()
+            .get_one(\"User-Agent\")
+            .map(|user_agent| user_agent.chars().take(USER_AGENT_MAX_LENGTH).collect());
+        let ip_address = request.remote().map(|address| address.ip().to_string());
+        Outcome::Success(ClientInfo {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+";
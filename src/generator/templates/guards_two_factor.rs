@@ -0,0 +1,94 @@
+pub static TEXT: &'static str = "use chrono::{Duration, Utc};
+
+use rocket::http::{Cookie, Status};
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request};
+
+use guards::user::CurrentUser;
+use libs::db::ConnPool;
+use libs::lib_const::TWO_FACTOR_PENDING_STR;
+use models::user::User;
+
+/// Minutes left to enter the second factor after the password.
+const TWO_FACTOR_PENDING_MINUTES: i64 = 5;
+
+#[derive(Serialize, Deserialize, FromForm)]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+/// A user whose password was verified, waiting for the second factor.
+///
+/// Kept as `<user_id>:<expires_at>` in a private cookie until a session is created.
+#[derive(Debug)]
+pub struct TwoFactorPending {
+    pub user_id: i32,
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for TwoFactorPending {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<TwoFactorPending, ()> {
+        let value = match request.cookies().get_private(TWO_FACTOR_PENDING_STR) {
+            Some(cookie) => cookie.value().to_string(),
+            None => return Outcome::Forward(()),
+        };
+        let mut parts = value.splitn(2, ':');
+        let user_id = parts.next().and_then(|user_id| user_id.parse::<i32>().ok());
+        let expires_at = parts.next().and_then(|expires_at| expires_at.parse::<i64>().ok());
+
+        match (user_id, expires_at) {
+            (Some(user_id), Some(expires_at)) if expires_at > Utc::now().timestamp() => {
+                Outcome::Success(TwoFactorPending { user_id })
+            }
+            _ => Outcome::Forward(()),
+        }
+    }
+}
+
+impl TwoFactorPending {
+    pub fn cookie(user_id: i32) -> Cookie<'static> {
+        let expires_at = (Utc::now() + Duration::minutes(TWO_FACTOR_PENDING_MINUTES)).timestamp();
+        Cookie::new(TWO_FACTOR_PENDING_STR, format!(\"{}:{}\", user_id, expires_at))
+    }
+
+    pub fn removal_cookie() -> Cookie<'static> {
+        Cookie::named(TWO_FACTOR_PENDING_STR)
+    }
+}
+
+/// The user enrolling a second factor, either signed in
+/// or pending because one of their roles requires it.
+#[derive(Debug)]
+pub struct TwoFactorUser {
+    pub user: User,
+    pub signed_in: bool,
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for TwoFactorUser {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<TwoFactorUser, ()> {
+        match request.guard::<CurrentUser>() {
+            Outcome::Success(current_user) => {
+                return Outcome::Success(TwoFactorUser {
+                    user: current_user.into_user(),
+                    signed_in: true,
+                })
+            }
+            Outcome::Failure(failure) => return Outcome::Failure(failure),
+            Outcome::Forward(_) => {}
+        }
+        let pending = request.guard::<TwoFactorPending>()?;
+        let db = request.guard::<ConnPool>()?;
+        match User::from_id(pending.user_id, &db) {
+            Ok(Some(user)) => Outcome::Success(TwoFactorUser {
+                user,
+                signed_in: false,
+            }),
+            Ok(None) => Outcome::Forward(()),
+            Err(_) => Outcome::Failure((Status::ServiceUnavailable, ())),
+        }
+    }
+}
+";
@@ -1,9 +1,16 @@
-pub static TEXT: &'static str = "use validator::Validate;
+pub static TEXT: &'static str = "use chrono::Utc;
+use validator::Validate;
 
-use rocket::outcome::IntoOutcome;
-use rocket::request::{FromRequest, Outcome, Request};
+use rocket::http::{Cookie, Status};
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request, State};
 
-use libs::lib_const::CURRENT_USER_STR;
+use libs::db::ConnPool;
+use libs::lib_const::SESSION_TOKEN_STR;
+use libs::request_log::set_user;
+use libs::settings::Settings;
+use models::session::Session;
+use models::user::User;
 
 #[derive(Validate, Serialize, Deserialize, FromForm)]
 pub struct Registration {
@@ -21,25 +28,148 @@ pub struct Login {
     pub password: String,
 }
 
+/// Credentials of the token API, `code` is the second factor of the users who enabled it.
+#[derive(Serialize, Deserialize)]
+pub struct TokenRequest {
+    pub email: String,
+    pub password: String,
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, FromForm)]
+pub struct ConfirmationRequest {
+    pub email: String,
+}
+
+/// The signed in user, from an active server-side session.
+///
+/// Forwards without a session cookie. An expired or revoked session, or a deleted user,
+/// fails with 401, answered by a redirect to the signin page in `handlers.rs`.
 #[derive(Debug)]
-pub struct CurrentUser(i32);
+pub struct CurrentUser {
+    user: User,
+    session_id: i32,
+}
 
 impl<'a, 'r> FromRequest<'a, 'r> for CurrentUser {
     type Error = ();
 
-    fn from_request(request: &'a Request<'r>) -> Outcome<CurrentUser, ()> {
-        request
-            .cookies()
-            .get_private(CURRENT_USER_STR)
-            .and_then(|cookie| cookie.value().parse().ok())
-            .map(CurrentUser)
-            .or_forward(())
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<CurrentUser, ()> {
+        let token = match request.cookies().get_private(SESSION_TOKEN_STR) {
+            Some(cookie) => cookie.value().to_string(),
+            None => return Outcome::Forward(()),
+        };
+        let db = request.guard::<ConnPool>()?;
+        let settings = request.guard::<State<Settings>>()?;
+        let security = &settings.application_security;
+
+        let session = match Session::find_active(&token, security, &db) {
+            Ok(Some(session)) => session,
+            Ok(None) => return expired_session(request),
+            Err(_) => return Outcome::Failure((Status::ServiceUnavailable, ())),
+        };
+        let user = match User::from_id(session.user_id, &db) {
+            Ok(Some(user)) => user,
+            Ok(None) => return expired_session(request),
+            Err(_) => return Outcome::Failure((Status::ServiceUnavailable, ())),
+        };
+
+        // Browsing is tracked on the session only, `last_access` of the user dates
+        // the API token. A failed refresh only shortens the idle timeout.
+        if session.last_seen_at < Utc::now().naive_utc() - security.activity_refresh_duration() {
+            let _ = session.touch(&db);
+        }
+        set_user(&user);
+
+        Outcome::Success(CurrentUser {
+            user,
+            session_id: session.id,
+        })
     }
 }
 
 impl CurrentUser {
     pub fn id(&self) -> i32 {
+        self.user.id
+    }
+
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    pub fn into_user(self) -> User {
+        self.user
+    }
+}
+
+fn expired_session<T>(request: &Request) -> request::Outcome<T, ()> {
+    request
+        .cookies()
+        .remove_private(Cookie::named(SESSION_TOKEN_STR));
+    Outcome::Failure((Status::Unauthorized, ()))
+}
+
+/// The user of a valid `Authorization: Bearer <access_token>`, for the APIs.
+///
+/// Fails with 401 otherwise, answered by the JSON catcher of `handlers.rs`.
+#[derive(Debug)]
+pub struct ApiUser(User);
+
+impl<'a, 'r> FromRequest<'a, 'r> for ApiUser {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<ApiUser, ()> {
+        let authorization = request.headers().get_one(\"Authorization\");
+        let access_token = match authorization.and_then(bearer_token) {
+            Some(access_token) => access_token,
+            None => return Outcome::Failure((Status::Unauthorized, ())),
+        };
+        let db = request.guard::<ConnPool>()?;
+        let settings = request.guard::<State<Settings>>()?;
+        let access_token_timeout = settings.application_security.access_token_duration();
+
+        match User::from_access_token(access_token, &db) {
+            Ok(Some(mut user)) => {
+                if !user.has_valid_access_token(access_token_timeout) {
+                    return Outcome::Failure((Status::Unauthorized, ()));
+                }
+                // A failed touch only shortens the token validity
+                let _ = user.touch_access_token(&db);
+                set_user(&user);
+                Outcome::Success(ApiUser(user))
+            }
+            Ok(None) => Outcome::Failure((Status::Unauthorized, ())),
+            Err(_) => Outcome::Failure((Status::ServiceUnavailable, ())),
+        }
+    }
+}
+
+impl ApiUser {
+    pub fn user(&self) -> &User {
+        &self.0
+    }
+
+    pub fn into_user(self) -> User {
         self.0
     }
 }
+
+fn bearer_token(authorization: &str) -> Option<&str> {
+    let mut parts = authorization.splitn(2, ' ');
+    match (parts.next(), parts.next()) {
+        (Some(scheme), Some(token)) if scheme.eq_ignore_ascii_case(\"Bearer\") => {
+            let token = token.trim();
+            if token.is_empty() {
+                None
+            } else {
+                Some(token)
+            }
+        }
+        _ => None,
+    }
+}
 ";
@@ -6,9 +6,11 @@ use diesel::result::Error;
 use rocket::Catcher;
 use rocket::http::{Status, ContentType};
 use rocket::request::Request;
-use rocket::response::{Response, Responder};
+use rocket::response::{Flash, Redirect, Response, Responder};
 use rocket_contrib::JsonValue;
 
+use libs::rate_limit::retry_after;
+
 /// Put all of your catchers here
 pub fn init_catchers() -> Vec<Catcher> {
     catchers![
@@ -16,6 +18,7 @@ pub fn init_catchers() -> Vec<Catcher> {
         unauthorized_handler,
         forbidden_handler,
         not_found_handler,
+        too_many_requests_handler,
         internal_server_error_handler,
         service_unavailable_handler,
     ]
@@ -26,9 +29,17 @@ fn bad_request_handler() -> APIResponse {
     bad_request()
 }
 
+/// JSON for the APIs, otherwise the browser session is gone so sign in again.
 #[catch(401)]
-fn unauthorized_handler() -> APIResponse {
-    unauthorized()
+fn unauthorized_handler(request: &Request) -> Result<APIResponse, Flash<Redirect>> {
+    if request.uri().path().starts_with(\"/apis\") {
+        Ok(unauthorized())
+    } else {
+        Err(Flash::error(
+            Redirect::to(\"/users/signin\"),
+            \"Your session has expired, please sign in again.\",
+        ))
+    }
 }
 
 #[catch(403)]
@@ -41,6 +52,14 @@ fn not_found_handler() -> APIResponse {
     not_found()
 }
 
+#[catch(429)]
+fn too_many_requests_handler(request: &Request) -> RetryAfter {
+    RetryAfter {
+        response: too_many_requests(),
+        seconds: retry_after(request),
+    }
+}
+
 #[catch(500)]
 fn internal_server_error_handler() -> APIResponse {
     internal_server_error()
@@ -91,6 +110,22 @@ impl<'r> Responder<'r> for APIResponse {
     }
 }
 
+/// `APIResponse` with a `Retry-After` header.
+#[derive(Debug)]
+pub struct RetryAfter {
+    response: APIResponse,
+    seconds: i64,
+}
+
+impl<'r> Responder<'r> for RetryAfter {
+    fn respond_to(self, req: &Request) -> Result<Response<'r>, Status> {
+        let response = self.response.respond_to(req)?;
+        Response::build_from(response)
+            .raw_header(\"Retry-After\", self.seconds.max(1).to_string())
+            .ok()
+    }
+}
+
 pub fn ok() -> APIResponse {
     APIResponse {
         data: json!(null),
@@ -168,6 +203,13 @@ pub fn unprocessable_entity(errors: JsonValue) -> APIResponse {
     }
 }
 
+pub fn too_many_requests() -> APIResponse {
+    APIResponse {
+        data: json!({\"message\": \"Too Many Requests\"}),
+        status: Status::TooManyRequests,
+    }
+}
+
 pub fn internal_server_error() -> APIResponse {
     APIResponse {
         data: json!({\"message\": \"Internal Server Error\"}),
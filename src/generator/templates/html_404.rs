@@ -1,4 +1,4 @@
-pub static TEXT: &'static str = "{% extends \"base_layout\" %}
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
 
 {% block content %}
   <h1>404</h1>
@@ -9,5 +9,5 @@ pub static TEXT: &'static str = "{% extends \"base_layout\" %}
   </p>
 
   <a href=\"/home\">Home</a>
-{% endblock content %}
+{% endblock content %}{% endraw %}
 ";
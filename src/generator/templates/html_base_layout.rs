@@ -1,11 +1,11 @@
-pub static TEXT: &'static str = "{% import \"macros\" as macros %}
+pub static TEXT: &'static str = "{% raw %}{% import \"macros\" as macros %}
 <!DOCTYPE html>
 <html>
 <head>
   <meta charset=\"utf-8\" />
   <meta http-equiv=\"X-UA-Compatible\" content=\"IE=edge\">
   <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
-  <meta name=\"author\" content=\"Karuna Murti\">
+  <meta name=\"author\" content=\"{% endraw %}{{authors_name_email}}{% raw %}\">
   <meta name=\"description\" content=\"This is Hangar, an opionated full fledged web framework for rust.\">
   <title>{{header.title}}</title>
   <link rel=\"icon\" type=\"image/svg\" sizes=\"any\" href={{macros::asset_url(filename=\"favicon.svg\")}} />
@@ -19,10 +19,12 @@ pub static TEXT: &'static str = "{% import \"macros\" as macros %}
         {% block content %}{% endblock content %}
       </div>
     </section>
+    {% if payload.debug_context %}
     <section class=\"container\" id=\"debug\">
       <pre>{{__tera_context}}</pre>
     </section>
+    {% endif %}
   </main>
 </body>
-</html>
+</html>{% endraw %}
 ";
\ No newline at end of file
@@ -0,0 +1,11 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<form action=\"/confirmations/create\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <label for=\"email\">Email</label>
+  <input type=\"text\" name=\"email\" id=\"email\" /><br/>
+  <input type=\"submit\" value=\"Resend confirmation instructions\" />
+</form>
+{% endblock content %}{% endraw %}";
@@ -1,7 +1,7 @@
-pub static TEXT: &'static str = "{% extends \"base_layout\" %}
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
 
 {% block content %}
     <h1>Hello World</h1>
     <p>Content here...</p>
-{% endblock content %}
+{% endblock content %}{% endraw %}
 ";
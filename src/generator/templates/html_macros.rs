@@ -1,3 +1,9 @@
-pub static TEXT: &'static str = "{% macro asset_url(filename) %}
+pub static TEXT: &'static str = "{% raw %}{% macro asset_url(filename) %}
 \"/assets/{{ filename }}\"
-{% endmacro asset_url %}";
+{% endmacro asset_url %}
+
+{# Macros can't read the context: `{{ macros::csrf_field(token=payload.csrf_token) }}`,
+   as the first field of the form, only the beginning of the body is checked #}
+{% macro csrf_field(token) %}
+<input type=\"hidden\" name=\"csrf_token\" value=\"{{ token }}\" />
+{% endmacro csrf_field %}{% endraw %}";
@@ -0,0 +1,14 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<form action=\"/password_resets/update\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <input type=\"hidden\" name=\"token\" value=\"{{body.token}}\" />
+  <label for=\"password\">New Password</label>
+  <input type=\"password\" name=\"password\" id=\"password\" /><br/>
+  <label for=\"password_confirmation\">New Password Confirmation</label>
+  <input type=\"password\" name=\"password_confirmation\" id=\"password_confirmation\"><br/>
+  <input type=\"submit\" value=\"Reset password\" />
+</form>
+{% endblock content %}{% endraw %}";
@@ -0,0 +1,11 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<form action=\"/password_resets/create\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <label for=\"email\">Email</label>
+  <input type=\"text\" name=\"email\" id=\"email\" /><br/>
+  <input type=\"submit\" value=\"Send password reset instructions\" />
+</form>
+{% endblock content %}{% endraw %}";
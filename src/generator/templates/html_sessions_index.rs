@@ -0,0 +1,37 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<h1>Your active sessions</h1>
+<table>
+  <tr>
+    <th>Device</th>
+    <th>IP</th>
+    <th>Signed in</th>
+    <th>Last seen</th>
+    <th></th>
+  </tr>
+  {% for session in body.sessions %}
+  <tr>
+    <td>{% if session.user_agent %}{{ session.user_agent }}{% else %}Unknown{% endif %}</td>
+    <td>{% if session.ip_address %}{{ session.ip_address }}{% else %}Unknown{% endif %}</td>
+    <td>{{ session.created_at }}</td>
+    <td>{{ session.last_seen_at }}</td>
+    <td>
+      {% if session.id == body.current_session_id %}
+      This device
+      {% else %}
+      <form action=\"/sessions/revoke/{{ session.id }}\" method=\"post\">
+        {{ macros::csrf_field(token=payload.csrf_token) }}
+        <input type=\"submit\" value=\"Sign out\" />
+      </form>
+      {% endif %}
+    </td>
+  </tr>
+  {% endfor %}
+</table>
+<form action=\"/sessions/revoke_all\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <input type=\"submit\" value=\"Sign out all other sessions\" />
+</form>
+{% endblock content %}{% endraw %}";
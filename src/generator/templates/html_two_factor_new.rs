@@ -0,0 +1,26 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<h1>Two-factor authentication</h1>
+{% if body.enabled %}
+<p>Two-factor authentication is enabled. Enter a code to disable it.</p>
+<form action=\"/two_factor/disable\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <label for=\"code\">Code</label>
+  <input type=\"text\" name=\"code\" id=\"code\" autocomplete=\"one-time-code\" /><br/>
+  <input type=\"submit\" value=\"Disable\" />
+</form>
+{% else %}
+<p>Scan this QR code with your authenticator app, then enter the code it shows.</p>
+{% if body.qr_code_svg %}<div>{{ body.qr_code_svg | safe }}</div>{% endif %}
+<p>Or enter this key manually: <code>{{ body.secret }}</code></p>
+<p><a href=\"{{ body.provisioning_uri }}\">Open in your authenticator app</a></p>
+<form action=\"/two_factor/enable\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <label for=\"code\">Code</label>
+  <input type=\"text\" name=\"code\" id=\"code\" autocomplete=\"one-time-code\" /><br/>
+  <input type=\"submit\" value=\"Enable\" />
+</form>
+{% endif %}
+{% endblock content %}{% endraw %}";
@@ -0,0 +1,13 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+
+{% block content %}
+<h1>Recovery codes</h1>
+<p>Two-factor authentication is enabled. Keep these codes somewhere safe,
+each one signs you in once if you lose your authenticator app. They won't be shown again.</p>
+<ul>
+  {% for code in body.codes %}
+  <li><code>{{ code }}</code></li>
+  {% endfor %}
+</ul>
+<a href=\"/\">Continue</a>
+{% endblock content %}{% endraw %}";
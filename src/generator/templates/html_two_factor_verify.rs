@@ -0,0 +1,12 @@
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
+
+{% block content %}
+<h1>Two-factor authentication</h1>
+<form action=\"/two_factor/authenticate\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
+  <label for=\"code\">Code from your authenticator app, or a recovery code</label>
+  <input type=\"text\" name=\"code\" id=\"code\" autocomplete=\"one-time-code\" /><br/>
+  <input type=\"submit\" value=\"Verify\" />
+</form>
+{% endblock content %}{% endraw %}";
@@ -1,8 +1,10 @@
-pub static TEXT: &'static str = "{% extends \"base_layout\" %}
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
 
 {% block content %}
   <h1>User</h1>
   <div>{{ body.email }}</div>
   <div>{{ body.id }}</div>
-{% endblock content %}
+  <a href=\"/sessions\">Your active sessions</a>
+  <a href=\"/two_factor/new\">Two-factor authentication</a>
+{% endblock content %}{% endraw %}
 ";
@@ -1,11 +1,15 @@
-pub static TEXT: &'static str = "{% extends \"base_layout\" %}
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
 
 {% block content %}
 <form action=\"/users/login\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
   <label for=\"email\">Email</label>
   <input type=\"text\" name=\"email\" id=\"email\" /><br/>
   <label for=\"password\">Password</label>
   <input type=\"password\" name=\"password\" id=\"password\" /><br/>
   <input type=\"submit\" value=\"Submit\" />
 </form>
-{% endblock content %}";
+<a href=\"/password_resets/new\">Forgot your password?</a>
+<a href=\"/confirmations/new\">Didn't receive confirmation instructions?</a>
+{% endblock content %}{% endraw %}";
@@ -1,7 +1,9 @@
-pub static TEXT: &'static str = "{% extends \"base_layout\" %}
+pub static TEXT: &'static str = "{% raw %}{% extends \"base_layout\" %}
+{% import \"macros\" as macros %}
 
 {% block content %}
 <form action=\"/users/register\" method=\"post\">
+  {{ macros::csrf_field(token=payload.csrf_token) }}
   <label for=\"email\">Email</label>
   <input type=\"text\" name=\"email\" id=\"email\" value=\"{{body.email}}\" /><br/>
   <label for=\"password\">Password</label>
@@ -10,4 +12,4 @@ pub static TEXT: &'static str = "{% extends \"base_layout\" %}
   <input type=\"password\" name=\"password_confirmation\" id=\"password_confirmation\"><br/>
   <input type=\"submit\" value=\"Submit\" />
 </form>
-{% endblock content %}";
+{% endblock content %}{% endraw %}";
@@ -23,14 +23,19 @@ extern crate validator_derive;
 extern crate validator;
 extern crate argon2rs;
 extern crate chrono;
+extern crate dotenv;
 extern crate r2d2;
 extern crate r2d2_diesel;
+extern crate qrcode;
 extern crate rand;
 extern crate ring;
 extern crate uuid;
 extern crate error_chain;
+extern crate flate2;
 #[macro_use]
 extern crate slog;
+extern crate slog_json;
+extern crate slog_term;
 extern crate sloggers;
 extern crate ammonia;
 #[cfg(feature = \"multipart_form\")]
@@ -48,6 +53,7 @@ pub mod guards;
 pub mod handlers;
 pub mod libs;
 pub mod models;
+pub mod policies;
 pub mod schema;
 pub mod view_models;
 
@@ -55,7 +61,9 @@ use libs::init::init_rocket;
 use handlers::init_catchers;
 
 pub fn rocket_factory() -> Result<rocket::Rocket, String> {
-    let initted_rocket = init_rocket(rocket::ignite());
+    // Before Rocket reads `ROCKET_ENV` and the other variables, a missing `.env` is fine
+    dotenv::dotenv().ok();
+    let initted_rocket = init_rocket(rocket::ignite())?;
     let catched_rocket = initted_rocket.catch(init_catchers());
     Ok(catched_rocket)
 }
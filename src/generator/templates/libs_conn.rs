@@ -1,4 +1,5 @@
 pub static TEXT: &'static str = "use std::ops::Deref;
+use std::time::Duration;
 
 use r2d2::{self, ManageConnection, Pool, PooledConnection};
 
@@ -91,6 +92,12 @@ where
         let pool = Pool::builder().max_size(max_size).build(manager)?;
         Ok(pool)
     }
+
+    /// A connection of `pool`, waiting at most `timeout` for it, for guards choosing between pools.
+    pub fn from_pool_timeout(pool: &Pool<T>, timeout: Duration) -> Result<Conn<T>, r2d2::Error> {
+        let pooled_connection = pool.get_timeout(timeout)?;
+        Ok(Conn { pooled_connection })
+    }
 }
 
 impl<T> Deref for Conn<T>
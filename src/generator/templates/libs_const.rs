@@ -1,2 +1,4 @@
-pub static TEXT: &'static str = "pub const CURRENT_USER_STR: &str = \"user_id\";
+pub static TEXT: &'static str = "pub const SESSION_TOKEN_STR: &str = \"session_token\";
+pub const CSRF_TOKEN_STR: &str = \"csrf_token\";
+pub const TWO_FACTOR_PENDING_STR: &str = \"two_factor_pending\";
 ";
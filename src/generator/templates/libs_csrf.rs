@@ -0,0 +1,138 @@
+pub static TEXT: &'static str = "use ring::constant_time::verify_slices_are_equal;
+
+use rocket::http::{Cookie, Method, Status};
+use rocket::request::{self, FromRequest, Request};
+use rocket::{Data, Outcome};
+
+use libs::lib_const::CSRF_TOKEN_STR;
+use libs::token::generate_token;
+
+/// Unsafe requests with a missing or wrong token are rewritten to this path, answered by the 403 catcher.
+pub const CSRF_FAILURE_PATH: &str = \"/csrf/failure\";
+/// For requests which are not forms, e.g. from javascript.
+pub const CSRF_HEADER: &str = \"X-CSRF-Token\";
+const CSRF_FIELD: &str = \"csrf_token\";
+/// APIs are not protected, they don't authenticate with cookies.
+const EXEMPT_PATH: &str = \"/apis\";
+
+/// Reject every unsafe request which doesn't send back the token of the session,
+/// in `X-CSRF-Token` or in the `csrf_token` field of an url-encoded or multipart form.
+///
+/// Only the beginning of the body can be read before the handler, so forms send the token
+/// with `csrf_field` from `macros.html.tera` as their first field.
+pub fn protect(request: &mut Request, data: &Data) {
+    if is_safe(request.method()) || is_exempt(request.uri().path()) {
+        return;
+    }
+
+    let submitted_token = match request.headers().get_one(CSRF_HEADER) {
+        Some(token) => Some(token.to_string()),
+        None => body_token(request, data.peek()),
+    };
+    let valid = match submitted_token {
+        Some(token) => is_session_token(request, &token),
+        None => false,
+    };
+    if !valid {
+        request.set_method(Method::Get);
+        request.set_uri(String::from(CSRF_FAILURE_PATH));
+    }
+}
+
+/// Token of the session, issued on first use, for the forms of the views:
+/// `ViewModel::csrf_token` sets it for `csrf_field` from `macros.html.tera`.
+#[derive(Clone)]
+pub struct CsrfToken(String);
+
+impl CsrfToken {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for CsrfToken {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<CsrfToken, ()> {
+        Outcome::Success(request.local_cache(|| CsrfToken(session_token(request))).clone())
+    }
+}
+
+/// Always fails with 403, so rejected requests get the response of the 403 catcher.
+pub struct CsrfFailure;
+
+impl<'a, 'r> FromRequest<'a, 'r> for CsrfFailure {
+    type Error = ();
+
+    fn from_request(_request: &'a Request<'r>) -> request::Outcome<CsrfFailure, ()> {
+        Outcome::Failure((Status::Forbidden, ()))
+    }
+}
+
+#[get(\"/csrf/failure\")]
+pub fn failure(_failure: CsrfFailure) {}
+
+/// `csrf_token` of an url-encoded body, the token is hex so it needs no decoding.
+pub fn form_token(body: &str) -> Option<String> {
+    body.split('&')
+        .filter_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(CSRF_FIELD), Some(value)) => Some(value.to_string()),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+/// `csrf_token` part of a multipart body.
+pub fn multipart_token(body: &str) -> Option<String> {
+    let disposition = format!(\"name=\\\"{}\\\"\", CSRF_FIELD);
+    let part = &body[body.find(&disposition)?..];
+    let value = &part[part.find(\"\\r\\n\\r\\n\")? + 4..];
+    let end = value.find(\"\\r\\n\")?;
+    Some(value[..end].to_string())
+}
+
+fn body_token(request: &Request, body: &[u8]) -> Option<String> {
+    let content_type = request.content_type()?;
+    let body = String::from_utf8_lossy(body);
+    if content_type.is_form() {
+        form_token(&body)
+    } else if content_type.is_form_data() {
+        multipart_token(&body)
+    } else {
+        None
+    }
+}
+
+fn session_token(request: &Request) -> String {
+    let mut cookies = request.cookies();
+    if let Some(cookie) = cookies.get_private(CSRF_TOKEN_STR) {
+        return cookie.value().to_string();
+    }
+    let token = generate_token();
+    cookies.add_private(Cookie::new(CSRF_TOKEN_STR, token.clone()));
+    token
+}
+
+// A request without the cookie has no token to send back
+fn is_session_token(request: &Request, submitted: &str) -> bool {
+    match request.cookies().get_private(CSRF_TOKEN_STR) {
+        Some(cookie) => verify_slices_are_equal(submitted.as_bytes(), cookie.value().as_bytes()).is_ok(),
+        None => false,
+    }
+}
+
+fn is_safe(method: Method) -> bool {
+    match method {
+        Method::Get | Method::Head | Method::Options | Method::Trace => true,
+        _ => false,
+    }
+}
+
+// `/apis` and below, not `/apisfoo`
+fn is_exempt(path: &str) -> bool {
+    path == EXEMPT_PATH || path.starts_with(&format!(\"{}/\", EXEMPT_PATH))
+}
+";
@@ -1,4 +1,14 @@
-pub static TEXT: &'static str = "use r2d2_diesel::ConnectionManager;
+pub static TEXT: &'static str = "use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
+
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request, State};
 
 use {{db_connection_long}};
 
@@ -7,4 +17,103 @@ use super::conn::Conn;
 pub type DbConnection = {{db_connection_short}};
 
 pub type ConnPool = Conn<ConnectionManager<DbConnection>>;
+
+/// Name of the pool serving `ReadConn`.
+pub const REPLICA: &str = \"replica\";
+/// Longest wait for a connection of a named pool before falling back to the primary one.
+const NAMED_POOL_TIMEOUT_MILLIS: u64 = 500;
+/// A named pool which failed to give a connection is skipped for this long.
+const UNHEALTHY_SECONDS: i64 = 30;
+
+struct NamedPool {
+    pool: Pool<ConnectionManager<DbConnection>>,
+    // Unix time until which the pool is skipped
+    unhealthy_until: AtomicUsize,
+}
+
+/// The pools of `databases` in `Rocket.toml`, besides the primary one managed as is.
+#[derive(Default)]
+pub struct DatabasePools {
+    pools: BTreeMap<String, NamedPool>,
+}
+
+impl DatabasePools {
+    pub fn insert(&mut self, name: &str, pool: Pool<ConnectionManager<DbConnection>>) {
+        let named_pool = NamedPool {
+            pool,
+            unhealthy_until: AtomicUsize::new(0),
+        };
+        self.pools.insert(name.to_string(), named_pool);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Pool<ConnectionManager<DbConnection>>> {
+        self.pools.get(name).map(|named_pool| &named_pool.pool)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.pools.keys().map(String::as_str).collect()
+    }
+
+    /// A connection of the `name` pool, `None` when it is unknown or unhealthy.
+    pub fn connection(&self, name: &str) -> Option<ConnPool> {
+        let named_pool = self.pools.get(name)?;
+        let now = Utc::now().timestamp();
+        if named_pool.unhealthy_until.load(Ordering::Relaxed) as i64 > now {
+            return None;
+        }
+        let timeout = Duration::from_millis(NAMED_POOL_TIMEOUT_MILLIS);
+        match Conn::from_pool_timeout(&named_pool.pool, timeout) {
+            Ok(conn) => Some(conn),
+            Err(_) => {
+                let until = (now + UNHEALTHY_SECONDS) as usize;
+                named_pool.unhealthy_until.store(until, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+}
+
+/// Connection to the primary database, for handlers which write.
+pub struct WriteConn(pub ConnPool);
+
+impl<'a, 'r> FromRequest<'a, 'r> for WriteConn {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<WriteConn, ()> {
+        request.guard::<ConnPool>().map(WriteConn)
+    }
+}
+
+impl Deref for WriteConn {
+    type Target = ConnPool;
+
+    fn deref(&self) -> &ConnPool {
+        &self.0
+    }
+}
+
+/// Connection to the `replica` database, or to the primary one when there is no replica
+/// or it is unhealthy. Replicas lag behind, so read what was just written with `WriteConn`.
+pub struct ReadConn(pub ConnPool);
+
+impl<'a, 'r> FromRequest<'a, 'r> for ReadConn {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<ReadConn, ()> {
+        if let Outcome::Success(pools) = request.guard::<State<DatabasePools>>() {
+            if let Some(conn) = pools.connection(REPLICA) {
+                return Outcome::Success(ReadConn(conn));
+            }
+        }
+        request.guard::<ConnPool>().map(ReadConn)
+    }
+}
+
+impl Deref for ReadConn {
+    type Target = ConnPool;
+
+    fn deref(&self) -> &ConnPool {
+        &self.0
+    }
+}
 ";
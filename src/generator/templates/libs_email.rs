@@ -13,9 +13,13 @@ use lettre::smtp::extension::ClientId;
 use lettre::stub::StubEmailTransport;
 use lettre::smtp::client::net::DEFAULT_TLS_PROTOCOLS;
 
+use chrono::Utc;
+use email_format::Email;
 use native_tls::TlsConnector;
 
 use rocket::config::{Table, Value};
+pub use super::mailer_config::{mailer_base_url, mailer_from, MailerConfig};
+use super::metrics::record_mail;
 use super::settings::{unwrap_int, unwrap_str};
 
 pub enum Transport {
@@ -27,7 +31,27 @@ pub enum Transport {
 
 pub struct Mailer(pub Transport);
 
-pub struct MailerConfig(pub Table);
+/// Compose a plain text email from the configured `from` address and send it.
+pub fn send_text_email(
+    mailer_config: &MailerConfig,
+    to: &str,
+    subject: &str,
+    body: &str,
+) -> Result<String, String> {
+    let mut mailer = Mailer::new_from_config(&mailer_config.0)?;
+    let from = mailer_from(&mailer_config.0);
+    let date = Utc::now().to_rfc2822();
+    let mut email = Email::new(from.as_str(), date.as_str()).map_err(stringify_error)?;
+    email.set_to(to).map_err(stringify_error)?;
+    email.set_subject(subject).map_err(stringify_error)?;
+    email
+        .set_body(body.replace(\"\\n\", \"\\r\\n\").as_str())
+        .map_err(stringify_error)?;
+    let sendable_email = email.as_simple_sendable_email().map_err(String::from)?;
+    let result = mailer.send_email(&sendable_email);
+    record_mail(&result);
+    result
+}
 
 impl Mailer {
     pub fn new_from_config(mailer_config: &Table) -> Result<Mailer, String> {
@@ -279,6 +303,10 @@ fn generate_stub_email_transport() -> Result<Mailer, String> {
     Ok(Mailer(Transport::Stub(StubEmailTransport::new_positive())))
 }
 
+fn stringify_error<E: Debug>(error: E) -> String {
+    format!(\"{:?}\", error)
+}
+
 fn stringify_result<O: Debug, E: Debug>(result: Result<O, E>) -> Result<String, String> {
     match result {
         Ok(ok) => Ok(format!(\"{:?}\", ok).to_string()),
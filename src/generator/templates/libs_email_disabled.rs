@@ -0,0 +1,13 @@
+pub static TEXT: &'static str = "//! `email.rs` without the `email` feature: the same API, sending nothing.
+
+pub use super::mailer_config::{mailer_base_url, mailer_from, MailerConfig};
+
+pub fn send_text_email(
+    _mailer_config: &MailerConfig,
+    _to: &str,
+    _subject: &str,
+    _body: &str,
+) -> Result<String, String> {
+    Err(String::from(\"Mailer is not enabled\"))
+}
+";
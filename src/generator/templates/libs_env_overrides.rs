@@ -0,0 +1,220 @@
+pub static TEXT: &'static str = "use std::env;
+use std::fs;
+
+use rocket::config::{Config, Table, Value};
+
+/// Prefixes of the variables overriding `Rocket.toml`, the last one wins.
+const PREFIXES: [&'static str; 2] = [\"ROCKET_\", \"HANGAR_\"];
+/// Separates the keys of nested settings, as in `HANGAR_MAILER__SMTP_PASSWORD`.
+const NESTING_SEPARATOR: &'static str = \"__\";
+/// `<key>_file` gives the path of a file holding the value of `<key>`.
+const FILE_SUFFIX: &'static str = \"_file\";
+/// Settings Rocket reads itself, moved out of `extras` by `apply_rocket_settings`.
+const ROCKET_SETTINGS: [&'static str; 5] = [\"address\", \"port\", \"workers\", \"keep_alive\", \"secret_key\"];
+/// Settings of Rocket which can't be overridden here: `log` is used before the overrides
+/// are read and `tls` and `limits` are tables read by Rocket only.
+const FIXED_ROCKET_SETTINGS: [&'static str; 3] = [\"log\", \"tls\", \"limits\"];
+
+/// Apply the environment, and the `.env` loaded at startup, to the settings of `config`:
+/// - `DATABASE_URL`, shared with diesel-cli, sets `database_url`
+/// - `HANGAR_<KEY>` sets a setting and `HANGAR_<TABLE>__<KEY>` a nested one, like the mailer;
+///   Rocket already applies `ROCKET_<KEY>`, `ROCKET_<TABLE>__<KEY>` is handled here
+/// - `<key>_file`, in `Rocket.toml` or the environment, reads `<key>` from a file,
+///   for secrets mounted by an orchestrator
+///
+/// Overridden settings of Rocket, like `port` or `secret_key`, are kept in `extras`
+/// to be validated, then `apply_rocket_settings` hands them over to Rocket.
+pub fn apply(config: &mut Config) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if env::var(\"ROCKET_DATABASE_URL\").is_err() {
+        if let Ok(database_url) = env::var(\"DATABASE_URL\") {
+            config
+                .extras
+                .insert(String::from(\"database_url\"), Value::String(database_url));
+        }
+    }
+
+    for prefix in &PREFIXES {
+        let mut variables: Vec<(String, String)> = env::vars()
+            .filter(|&(ref name, _)| name.starts_with(prefix))
+            .collect();
+        variables.sort();
+        for (name, raw) in variables {
+            let key = name[prefix.len()..].to_lowercase();
+            let path: Vec<&str> = key.split(NESTING_SEPARATOR).collect();
+            if path.iter().any(|segment| segment.is_empty()) {
+                continue;
+            }
+            if FIXED_ROCKET_SETTINGS.contains(&path[0]) {
+                if *prefix != \"ROCKET_\" {
+                    errors.push(fixed_setting_error(&name, path[0]));
+                }
+            } else if path.len() == 1 {
+                if *prefix != \"ROCKET_\" {
+                    config.extras.insert(key.clone(), parse_value(&raw));
+                }
+            } else {
+                // Rocket keeps `ROCKET_MAILER__FROM` as a `mailer__from` setting
+                config.extras.remove(&key);
+                let table = config
+                    .extras
+                    .entry(path[0].to_string())
+                    .or_insert_with(|| Value::Table(Table::new()));
+                set_nested(table, &path[1..], parse_value(&raw));
+            }
+        }
+    }
+
+    if let Err(file_errors) = read_secret_files(config) {
+        errors.extend(file_errors);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Move the overridden settings of Rocket from `extras` to `config`, once validated.
+/// Rocket only reads them when it is created, with `rocket::custom`.
+pub fn apply_rocket_settings(config: &mut Config) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for key in &ROCKET_SETTINGS {
+        if let Some(value) = config.extras.remove(*key) {
+            if let Err(error) = set_rocket_setting(config, key, &value) {
+                errors.push(error);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn set_rocket_setting(config: &mut Config, key: &str, value: &Value) -> Result<(), String> {
+    let invalid = format!(\"`{}` is not valid for Rocket\", key);
+    match (key, value) {
+        (\"address\", &Value::String(ref address)) => {
+            config.set_address(address.as_str()).map_err(|_| invalid)
+        }
+        (\"port\", &Value::Integer(port)) => {
+            config.set_port(port as u16);
+            Ok(())
+        }
+        (\"workers\", &Value::Integer(workers)) => {
+            config.set_workers(workers as u16);
+            Ok(())
+        }
+        (\"keep_alive\", &Value::Integer(seconds)) => {
+            config.set_keep_alive(seconds as u32);
+            Ok(())
+        }
+        (\"secret_key\", &Value::String(ref secret_key)) => {
+            config.set_secret_key(secret_key.as_str()).map_err(|_| invalid)
+        }
+        _ => Err(invalid),
+    }
+}
+
+fn fixed_setting_error(name: &str, key: &str) -> String {
+    let reason = if key == \"log\" {
+        \"the logger starts before the overrides are read\"
+    } else {
+        \"Rocket reads it itself\"
+    };
+    format!(
+        \"`{}` can't override `{}`, {}: use `Rocket.toml` or `ROCKET_{}`\",
+        name,
+        key,
+        reason,
+        key.to_uppercase()
+    )
+}
+
+// Like Rocket's own variables: a TOML value such as `true`, `8` or `[\"admin\"]`, otherwise a string
+fn parse_value(raw: &str) -> Value {
+    format!(\"value = {}\", raw)
+        .parse::<Value>()
+        .ok()
+        .and_then(|document| document.get(\"value\").cloned())
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+fn set_nested(target: &mut Value, path: &[&str], value: Value) {
+    if !target.is_table() {
+        *target = Value::Table(Table::new());
+    }
+    if let Value::Table(ref mut table) = *target {
+        if path.len() == 1 {
+            table.insert(path[0].to_string(), value);
+        } else {
+            let nested = table
+                .entry(path[0].to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            set_nested(nested, &path[1..], value);
+        }
+    }
+}
+
+// Top-level and table `<key>_file`s are replaced by `<key>`
+fn read_secret_files(config: &mut Config) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    for (file_key, path) in secret_files(config.extras.iter()) {
+        config.extras.remove(&file_key);
+        match read_secret(&file_key, &path) {
+            Ok(secret) => {
+                config.extras.insert(secret_key(&file_key), secret);
+            }
+            Err(error) => errors.push(error),
+        }
+    }
+    for value in config.extras.values_mut() {
+        if let Value::Table(ref mut table) = *value {
+            for (file_key, path) in secret_files(table.iter()) {
+                table.remove(&file_key);
+                match read_secret(&file_key, &path) {
+                    Ok(secret) => {
+                        table.insert(secret_key(&file_key), secret);
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn secret_files<'a, I>(settings: I) -> Vec<(String, String)>
+where
+    I: Iterator<Item = (&'a String, &'a Value)>,
+{
+    settings
+        .filter(|&(key, _)| key.ends_with(FILE_SUFFIX))
+        .filter_map(|(key, value)| value.as_str().map(|path| (key.clone(), path.to_string())))
+        .collect()
+}
+
+fn secret_key(file_key: &str) -> String {
+    file_key[..file_key.len() - FILE_SUFFIX.len()].to_string()
+}
+
+// A trailing newline, as left by most editors and `echo`, isn't part of the secret
+fn read_secret(file_key: &str, path: &str) -> Result<Value, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Value::String(
+            content.trim_right_matches(|c| c == '\\n' || c == '\\r').to_string(),
+        )),
+        Err(e) => Err(format!(\"`{}` can't read `{}`: {}\", file_key, path, e)),
+    }
+}
+";
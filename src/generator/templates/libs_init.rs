@@ -1,72 +1,158 @@
-pub static TEXT: &'static str = "use r2d2_diesel::ConnectionManager;
+pub static TEXT: &'static str = "use std::cmp;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
 
 use rocket::fairing::AdHoc;
-use rocket::{Config, Rocket, State};
-use super::conn::Conn;
+use rocket::{self, Config, Rocket};
+use super::csrf;
 use rocket_contrib::Template;
 
-use super::db::DbConnection;
+use super::db::{DatabasePools, DbConnection};
+use super::env_overrides;
 #[cfg(feature = \"email\")]
 use super::email::MailerConfig;
 use super::logger::{prepare_logger, CombinedLogger};
-use super::log_format::Common;
+use super::metrics::{self, Metrics};
+use super::rate_limit::RateLimiter;
+use super::request_log;
 use super::routes::routes;
-use super::settings::Settings;
+use super::settings::{Database, Settings};
+use super::settings_validation;
 
-use controllers::assets_controller;
+use controllers::{assets_controller, metrics_controller};
+use view_models::DEBUG_CONTEXT;
 
-pub fn init_rocket(rocket: Rocket) -> Rocket {
-    let attached_rocket = rocket
+/// Rocket is created again from its settings with the overrides, as it only reads
+/// its own ones, like `port` or `secret_key`, when it is created.
+/// Invalid settings abort the launch rather than running with defaults.
+pub fn init_rocket(rocket: Rocket) -> Result<Rocket, String> {
+    let environment = rocket.config().environment;
+    let local_config = load_config(rocket.config())
+        .map_err(|errors| settings_validation::report(environment, &errors))?;
+    let attached_rocket = rocket::custom(local_config, false)
+        .attach(AdHoc::on_request(|req, _| request_log::on_request(req)))
         .attach(AdHoc::on_request(|req, _| {
             let new_uri = req.uri().as_str().to_lowercase();
             req.set_uri(new_uri);
         }))
+        .attach(AdHoc::on_request(|req, data| csrf::protect(req, data)))
         .attach(AdHoc::on_attach(|rocket| {
-            let generated_rocket = setup_state(rocket);
-            Ok(generated_rocket)
+            let local_config = rocket.config().clone();
+            setup_state(rocket, local_config)
         }))
         .attach(Template::fairing())
-        .attach(AdHoc::on_response(|req, res| {
-            let logger = req.guard::<State<CombinedLogger>>().unwrap();
-            let log = Common::log(req, res);
-            if logger.terminal_logger.is_some() {
-                let terminal_logger = logger.terminal_logger.as_ref().unwrap();
-                info!(terminal_logger, \"{}\", log);
-            }
-            if logger.file_logger.is_some() {
-                let file_logger = logger.file_logger.as_ref().unwrap();
-                info!(file_logger, \"{}\", log);
-            }
-        }));
-    routes(attached_rocket)
+        .attach(AdHoc::on_response(|req, res| request_log::on_response(req, res)))
+        .attach(AdHoc::on_response(|req, res| metrics::on_response(req, res)));
+    Ok(routes(attached_rocket))
 }
 
+/// `Rocket.toml` with the environment overrides applied, checked before anything uses it.
+fn load_config(rocket_config: &Config) -> Result<Config, Vec<String>> {
+    let mut local_config = rocket_config.clone();
+    env_overrides::apply(&mut local_config)?;
+    settings_validation::validate(&local_config)?;
+    env_overrides::apply_rocket_settings(&mut local_config)?;
+    Ok(local_config)
+}
+
+/// Longest wait between two attempts to reach the database at startup.
+const MAX_CONNECT_BACKOFF_SECONDS: u64 = 30;
+
 /// put your state and middleware here
-fn setup_state(rocket: Rocket) -> Rocket {
-    let local_config = rocket.config().clone();
+fn setup_state(rocket: Rocket, local_config: Config) -> Result<Rocket, Rocket> {
     let local_settings = Settings::new_from_config(&local_config);
 
+    // Logger
+    let combined_logger = prepare_logger(&local_settings.our_logger);
     // Database
-    let database_url = local_settings.database.database_url.clone();
-    let database_pool = local_settings.database.database_pool;
-    let database_manager = ConnectionManager::<DbConnection>::new(database_url);
-    let diesel_pool = Conn::init_pool(database_manager, database_pool).unwrap();
+    let diesel_pool = match connect_database(&local_settings.database, &combined_logger) {
+        Ok(diesel_pool) => diesel_pool,
+        Err(message) => {
+            combined_logger.error(&message);
+            return Err(rocket);
+        }
+    };
+    let database_pools = connect_named_databases(&local_settings.database);
     // Assets
     let manage_asset = local_settings.assets.serve_assets;
-    // Logger
-    let combined_logger = prepare_logger(&local_settings.our_logger);
+    // Metrics
+    let metrics_enabled = local_settings.metrics.enabled;
+    let metrics_path = local_settings.metrics.path.clone();
+    // Views
+    DEBUG_CONTEXT.store(local_settings.views.debug_context, Ordering::Relaxed);
+    // Rate limiting
+    let rate_limiter = RateLimiter::new(&local_settings.rate_limits, &diesel_pool);
 
-    let managed_rocket = rocket.manage(diesel_pool).manage(local_settings);
+    let managed_rocket = rocket
+        .manage(diesel_pool)
+        .manage(database_pools)
+        .manage(local_settings)
+        .manage(rate_limiter);
     let assetable_rocket = get_assetable_rocket(managed_rocket, manage_asset);
-    let loggable_rocket = assetable_rocket.manage(combined_logger);
+    let measurable_rocket = get_measurable_rocket(assetable_rocket, metrics_enabled, &metrics_path);
+    let loggable_rocket = measurable_rocket.manage(combined_logger);
 
-    if cfg!(feature = \"email\") {
-        match set_mailer_config(&local_config) {
-            Ok(mailer) => loggable_rocket.manage(mailer),
-            Err(_) => loggable_rocket,
+    // Without it, the handlers skip sending emails
+    #[cfg(feature = \"email\")]
+    let loggable_rocket = match set_mailer_config(&local_config) {
+        Ok(mailer) => loggable_rocket.manage(mailer),
+        Err(_) => loggable_rocket,
+    };
+    Ok(loggable_rocket)
+}
+
+/// The pools of `databases`, which don't wait for their database:
+/// `ReadConn` falls back to the primary one while they are unreachable.
+fn connect_named_databases(database: &Database) -> DatabasePools {
+    let mut database_pools = DatabasePools::default();
+    for (name, named_database) in &database.databases {
+        let manager = ConnectionManager::<DbConnection>::new(named_database.database_url.clone());
+        let pool = database
+            .pool_builder()
+            .max_size(named_database.database_pool)
+            .min_idle(named_database.database_min_idle)
+            .build_unchecked(manager);
+        database_pools.insert(name, pool);
+    }
+    database_pools
+}
+
+/// The pool, retrying with a growing backoff while the database is unreachable,
+/// e.g. when it starts alongside the app.
+fn connect_database(
+    database: &Database,
+    logger: &CombinedLogger,
+) -> Result<Pool<ConnectionManager<DbConnection>>, String> {
+    let mut backoff = database.connect_backoff();
+    let mut attempt = 0;
+    loop {
+        let manager = ConnectionManager::<DbConnection>::new(database.database_url.clone());
+        match database.pool_builder().build(manager) {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < database.database_connect_retries => {
+                attempt += 1;
+                logger.warn(&format!(
+                    \"Database unreachable ({}), retry {} of {} in {} ms\",
+                    e,
+                    attempt,
+                    database.database_connect_retries,
+                    backoff.as_secs() * 1000 + u64::from(backoff.subsec_nanos()) / 1_000_000
+                ));
+                thread::sleep(backoff);
+                backoff = cmp::min(backoff * 2, Duration::from_secs(MAX_CONNECT_BACKOFF_SECONDS));
+            }
+            Err(e) => {
+                return Err(format!(
+                    \"Database unreachable after {} attempts, not launching: {}\",
+                    attempt + 1,
+                    e
+                ))
+            }
         }
-    } else {
-        loggable_rocket
     }
 }
 
@@ -78,6 +164,16 @@ fn get_assetable_rocket(managed_rocket: Rocket, manage_asset: bool) -> Rocket {
     }
 }
 
+fn get_measurable_rocket(rocket: Rocket, metrics_enabled: bool, metrics_path: &str) -> Rocket {
+    if metrics_enabled {
+        rocket
+            .manage(Metrics::default())
+            .mount(metrics_path, routes![metrics_controller::metrics])
+    } else {
+        rocket
+    }
+}
+
 #[cfg(feature = \"email\")]
 fn set_mailer_config(config: &Config) -> Result<MailerConfig, String> {
     match config.get_table(\"mailer\") {
@@ -1,5 +1,6 @@
 pub static TEXT: &'static str = "use chrono::prelude::Utc;
 
+use rocket::response::Body;
 use rocket::Request;
 use rocket::Response;
 
@@ -9,36 +10,88 @@ pub static ISO_8601_UTC: &'static str = \"%Y-%m-%dT%H:%M:%S%.3fZ\";
 // When offset from UTC != 0, then the offset is displayed instead of \"Z\".
 pub static ISO_8601_OFFSET: &'static str = \"%Y-%m-%dT%H:%M:%S%.3f%:z\";
 
-// TODO: fix :proto, :size, :userid, :user
-pub static COMMON_LOG_FORMAT: &'static str = \":host :userid :user [:time] \\\":method :url :proto\\\" :status :size\";
+// Only HTTP/1.1 is supported: Rocket doesn't expose the HTTP version of the request,
+// so the request line of the standard formats always says HTTP/1.1
+pub static COMMON_LOG_FORMAT: &'static str = \":host :userid :user [:time] \\\":method :url HTTP/1.1\\\" :status :size\";
+pub static COMBINED_LOG_FORMAT: &'static str = \":host :userid :user [:time] \\\":method :url HTTP/1.1\\\" :status :size \\\":referer\\\" \\\":user_agent\\\"\";
 
-pub struct Common;
+/// `common`, `combined`, or a format of `:token`s like `:method :url :status :latency`.
+pub fn resolve_format(access_log_format: &str) -> String {
+    match access_log_format {
+        \"common\" => COMMON_LOG_FORMAT.to_string(),
+        \"combined\" => COMBINED_LOG_FORMAT.to_string(),
+        format => format.to_string(),
+    }
+}
+
+/// What the request and response don't carry themselves.
+pub struct AccessContext<'a> {
+    pub request_id: &'a str,
+    pub user_id: Option<i32>,
+    pub user: Option<&'a str>,
+    pub latency_ms: f64,
+}
+
+pub struct AccessLog;
 
-impl Common {
-    pub fn log(req: &Request, res: &Response)-> String {
-        let host = req.remote();
-        let host = if host.is_some() {
-            format!(\"{}\", host.unwrap())
-        } else {
-            format!(\"\")
+impl AccessLog {
+    /// Substitute the `:token`s of `format`, unknown tokens are kept as is.
+    pub fn log(format: &str, req: &Request, res: &mut Response, context: &AccessContext) -> String {
+        let mut line = String::with_capacity(format.len() * 2);
+        let mut rest = format;
+        while let Some(start) = rest.find(':') {
+            line.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let end = after
+                .find(|c: char| !(c.is_ascii_lowercase() || c == '_'))
+                .unwrap_or_else(|| after.len());
+            let token = &after[..end];
+            match AccessLog::value(token, req, res, context) {
+                Some(value) => line.push_str(&value),
+                None => {
+                    line.push(':');
+                    line.push_str(token);
+                }
+            }
+            rest = &after[end..];
+        }
+        line.push_str(rest);
+        line
+    }
+
+    fn value(token: &str, req: &Request, res: &mut Response, context: &AccessContext) -> Option<String> {
+        let value = match token {
+            \"host\" => req.remote()
+                .map(|remote| remote.ip().to_string())
+                .unwrap_or_else(|| String::from(\"-\")),
+            \"userid\" => context
+                .user_id
+                .map(|user_id| user_id.to_string())
+                .unwrap_or_else(|| String::from(\"-\")),
+            \"user\" => context.user.unwrap_or(\"-\").to_string(),
+            \"time\" => Utc::now().format(CLF_TIME).to_string(),
+            \"method\" => req.method().as_str().to_string(),
+            \"url\" => req.uri().as_str().to_string(),
+            \"status\" => res.status().code.to_string(),
+            \"size\" => match res.body() {
+                Some(Body::Sized(_, size)) => size.to_string(),
+                Some(Body::Chunked(..)) => String::from(\"-\"),
+                None => String::from(\"0\"),
+            },
+            \"referer\" => header(req, \"Referer\"),
+            \"user_agent\" => header(req, \"User-Agent\"),
+            \"latency\" => format!(\"{:.3}\", context.latency_ms),
+            \"request_id\" => context.request_id.to_string(),
+            _ => return None,
         };
-        let method = req.method().as_str();
-        let url = req.uri().as_str();
-        let status = format!(\"{}\", res.status().code);
-        let proto = \"-\";
-        let size = \"0\";
-        let userid = \"-\";
-        let user = \"-\";
-        let time = Utc::now().format(CLF_TIME);
-        COMMON_LOG_FORMAT
-            .replace(\":host\", &host)
-            .replace(\":userid\", userid)
-            .replace(\":user\", user)
-            .replace(\":time\", &format!(\"{}\", time))
-            .replace(\":method\", method)
-            .replace(\":url\", url)
-            .replace(\":proto\", proto)
-            .replace(\":status\", &status)
-            .replace(\":size\", size)
+        Some(value)
     }
-}";
+}
+
+fn header(req: &Request, name: &str) -> String {
+    req.headers()
+        .get_one(name)
+        .map(|value| value.replace('\"', \"\\\\\\\"\"))
+        .unwrap_or_else(|| String::from(\"-\"))
+}
+";
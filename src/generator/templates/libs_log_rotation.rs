@@ -0,0 +1,155 @@
+pub static TEXT: &'static str = "use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// When the log file is rotated, besides its size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RotateInterval {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl RotateInterval {
+    // The file is rotated when the period of the current time differs from the one of the file
+    fn period(&self, time: DateTime<Utc>) -> String {
+        match *self {
+            RotateInterval::Never => String::new(),
+            RotateInterval::Hourly => time.format(\"%Y%m%d%H\").to_string(),
+            RotateInterval::Daily => time.format(\"%Y%m%d\").to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RotationPolicy {
+    /// Size after which the file is rotated, 0 to never rotate on size.
+    pub max_size_bytes: u64,
+    pub interval: RotateInterval,
+    /// Rotated files kept, the oldest ones are deleted.
+    pub keep: usize,
+    /// Gzip the rotated files.
+    pub compress: bool,
+}
+
+/// Log file rotated to `<name>.<timestamp>`, or `<name>.<timestamp>.gz` when compressed.
+///
+/// Rotation only happens between lines, so an event is never split between files.
+pub struct RotatingFile {
+    path: PathBuf,
+    policy: RotationPolicy,
+    file: File,
+    size: u64,
+    period: String,
+    at_line_start: bool,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, policy: RotationPolicy) -> io::Result<RotatingFile> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = open_append(&path)?;
+        let metadata = file.metadata()?;
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        Ok(RotatingFile {
+            period: policy.interval.period(modified),
+            size: metadata.len(),
+            at_line_start: true,
+            path,
+            policy,
+            file,
+        })
+    }
+
+    fn needs_rotation(&self, incoming: usize) -> bool {
+        if !self.at_line_start || self.size == 0 {
+            return false;
+        }
+        let too_big = self.policy.max_size_bytes > 0
+            && self.size + incoming as u64 > self.policy.max_size_bytes;
+        too_big || self.policy.interval.period(Utc::now()) != self.period
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(\".{}\", Utc::now().format(\"%Y%m%dT%H%M%S%.3f\")));
+        let rotated = PathBuf::from(rotated);
+
+        fs::rename(&self.path, &rotated)?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        self.period = self.policy.interval.period(Utc::now());
+
+        if self.policy.compress {
+            compress(&rotated)?;
+        }
+        self.prune()
+    }
+
+    // Timestamps sort chronologically, so the first files are the oldest
+    fn prune(&self) -> io::Result<()> {
+        let (dir, file_name) = match (self.path.parent(), self.path.file_name()) {
+            (Some(dir), Some(file_name)) => (dir, file_name.to_string_lossy().into_owned()),
+            _ => return Ok(()),
+        };
+        let prefix = format!(\"{}.\", file_name);
+        let mut rotated: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .map_or(false, |name| name.to_string_lossy().starts_with(&prefix))
+            })
+            .collect();
+        rotated.sort();
+
+        let excess = rotated.len().saturating_sub(self.policy.keep);
+        for path in rotated.iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.needs_rotation(buf.len()) {
+            // A failed rotation keeps writing to the current file, it is retried on the next line
+            let _ = self.rotate();
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        if written > 0 {
+            self.at_line_start = buf[written - 1] == b'\\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(\".gz\");
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(compressed)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+";
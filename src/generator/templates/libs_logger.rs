@@ -1,20 +1,100 @@
-pub static TEXT: &'static str = "use std::fs::File;
-use std::io::Error;
-use std::path::Path;
+pub static TEXT: &'static str = "use std::io::{self, Error, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use slog::Logger;
+use slog::{Drain, Level, LevelFilter, Logger};
+use slog_json::Json;
+use slog_term::{FullFormat, PlainSyncDecorator};
 use sloggers::Build;
 use sloggers::terminal::{Destination, TerminalLoggerBuilder};
-use sloggers::file::FileLoggerBuilder;
 use sloggers::types::Severity;
 
 use rocket::config::Environment;
 
-use super::settings::OurLogger;
+use super::log_rotation::{RotatingFile, RotationPolicy};
+use super::settings::{LogFormat, OurLogger};
 
 pub struct CombinedLogger {
     pub terminal_logger: Option<Logger>,
     pub file_logger: Option<Logger>,
+    pub log_format: LogFormat,
+}
+
+/// One handled request, logged by the response fairing of `libs/request_log.rs`.
+pub struct AccessEvent<'a> {
+    pub request_id: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub status: u16,
+    pub latency_ms: f64,
+    pub user_id: Option<i32>,
+}
+
+impl CombinedLogger {
+    /// Security relevant events, like lockouts, logged to every logger.
+    pub fn audit(&self, message: &str) {
+        for logger in self.loggers() {
+            warn!(logger, \"[audit] {}\", message);
+        }
+    }
+
+    pub fn info(&self, message: &str) {
+        for logger in self.loggers() {
+            info!(logger, \"{}\", message);
+        }
+    }
+
+    pub fn warn(&self, message: &str) {
+        for logger in self.loggers() {
+            warn!(logger, \"{}\", message);
+        }
+    }
+
+    pub fn error(&self, message: &str) {
+        for logger in self.loggers() {
+            error!(logger, \"{}\", message);
+        }
+    }
+
+    /// Log a handled request, `line` is used by the text format and the fields by the json one.
+    pub fn access(&self, line: &str, event: &AccessEvent) {
+        for logger in self.loggers() {
+            match self.log_format {
+                LogFormat::Text => info!(logger, \"{}\", line),
+                LogFormat::Json => info!(
+                    logger,
+                    \"{} {} {}\", event.method, event.path, event.status;
+                    \"request_id\" => event.request_id,
+                    \"method\" => event.method,
+                    \"path\" => event.path,
+                    \"status\" => event.status,
+                    \"latency_ms\" => event.latency_ms,
+                    \"user_id\" => event.user_id
+                ),
+            }
+        }
+    }
+
+    /// The same loggers with `request_id` added to every event.
+    pub fn with_request_id(&self, request_id: &str) -> CombinedLogger {
+        let request_id = request_id.to_string();
+        CombinedLogger {
+            terminal_logger: self.terminal_logger
+                .as_ref()
+                .map(|logger| logger.new(o!(\"request_id\" => request_id.clone()))),
+            file_logger: self.file_logger
+                .as_ref()
+                .map(|logger| logger.new(o!(\"request_id\" => request_id.clone()))),
+            log_format: self.log_format,
+        }
+    }
+
+    fn loggers(&self) -> Vec<&Logger> {
+        self.terminal_logger
+            .iter()
+            .chain(self.file_logger.iter())
+            .collect()
+    }
 }
 
 fn return_terminal_logger(log_level: &Severity) -> Logger {
@@ -24,32 +104,60 @@ fn return_terminal_logger(log_level: &Severity) -> Logger {
     builder.build().unwrap()
 }
 
-fn return_file_logger(log_level: &Severity, path: &str) -> Result<Logger, Error> {
+fn log_file_path(dir: &Path) -> PathBuf {
     let environment = Environment::active().expect(\"Unknown environment\");
     let filename = match environment {
         Environment::Development => \"development.log\",
         Environment::Staging => \"staging.log\",
         Environment::Production => \"production.log\",
     };
-    let mut string_path = String::from(path);
-    string_path.push_str(filename);
-    if !Path::new(&string_path).exists() {
-        File::create(&string_path)?;
+    dir.join(filename)
+}
+
+fn return_file_logger(log_level: &Severity, writer: RotatingFile) -> Logger {
+    let drain = FullFormat::new(PlainSyncDecorator::new(writer)).build().fuse();
+    let drain = LevelFilter::new(drain, as_level(log_level)).fuse();
+    Logger::root(drain, o!())
+}
+
+// One json object per line, with `ts`, `level` and `msg` before the fields of the event
+fn return_json_logger<W: Write + Send + 'static>(log_level: &Severity, writer: W) -> Logger {
+    let drain = Mutex::new(Json::new(writer).add_default_keys().build()).fuse();
+    let drain = LevelFilter::new(drain, as_level(log_level)).fuse();
+    Logger::root(drain, o!())
+}
+
+// The log directory is created if needed
+fn open_log_file(dir: &Path, policy: RotationPolicy) -> Result<RotatingFile, Error> {
+    RotatingFile::open(log_file_path(dir), policy)
+}
+
+fn as_level(log_level: &Severity) -> Level {
+    match *log_level {
+        Severity::Trace => Level::Trace,
+        Severity::Debug => Level::Debug,
+        Severity::Info => Level::Info,
+        Severity::Warning => Level::Warning,
+        Severity::Error => Level::Error,
+        Severity::Critical => Level::Critical,
     }
-    let mut builder = FileLoggerBuilder::new(&string_path);
-    builder.level(*log_level);
-    Ok(builder.build().unwrap())
 }
 
 pub fn prepare_logger(our_logger: &OurLogger) -> CombinedLogger {
+    let json = our_logger.log_format == LogFormat::Json;
     let terminal_logger = if our_logger.terminal_logger {
-        Some(return_terminal_logger(&our_logger.log_level))
+        if json {
+            Some(return_json_logger(&our_logger.log_level, io::stdout()))
+        } else {
+            Some(return_terminal_logger(&our_logger.log_level))
+        }
     } else {
         None
     };
     let file_logger = if our_logger.file_logger {
-        match return_file_logger(&our_logger.log_level, &our_logger.file_logger_dir_path) {
-            Ok(logger) => Some(logger),
+        match open_log_file(&our_logger.file_logger_dir_path, our_logger.rotation_policy()) {
+            Ok(file) if json => Some(return_json_logger(&our_logger.log_level, file)),
+            Ok(file) => Some(return_file_logger(&our_logger.log_level, file)),
             Err(_) => None,
         }
     } else {
@@ -58,6 +166,7 @@ pub fn prepare_logger(our_logger: &OurLogger) -> CombinedLogger {
     CombinedLogger {
         terminal_logger,
         file_logger,
+        log_format: our_logger.log_format,
     }
 }
 ";
@@ -0,0 +1,22 @@
+pub static TEXT: &'static str = "//! The `mailer` table of the settings, read with or without the `email` feature.
+
+use rocket::config::{Table, Value};
+
+pub struct MailerConfig(pub Table);
+
+/// `from` address of the mailer configuration, used as sender of every email.
+pub fn mailer_from(mailer_config: &Table) -> String {
+    match mailer_config.get(\"from\").and_then(Value::as_str) {
+        Some(from) => String::from(from),
+        None => String::from(\"noreply@localhost\"),
+    }
+}
+
+/// `base_url` of the mailer configuration, used to build links inside emails.
+pub fn mailer_base_url(mailer_config: &Table) -> String {
+    match mailer_config.get(\"base_url\").and_then(Value::as_str) {
+        Some(base_url) => String::from(base_url.trim_right_matches('/')),
+        None => String::from(\"http://localhost:8000\"),
+    }
+}
+";
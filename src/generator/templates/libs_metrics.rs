@@ -0,0 +1,227 @@
+pub static TEXT: &'static str = "use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
+
+use rocket::http::Status;
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request, Response, State};
+
+use super::db::{DatabasePools, DbConnection};
+use super::request_log;
+use super::settings::Settings;
+
+/// `pool` label of the pool managed as is.
+const PRIMARY_POOL: &str = \"primary\";
+/// Upper bounds, in seconds, of the request latency histogram.
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static MAILS_SENT: AtomicUsize = ATOMIC_USIZE_INIT;
+static MAILS_FAILED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+#[derive(Default)]
+struct RequestStats {
+    count: u64,
+    // Per bucket, made cumulative when rendered
+    buckets: [u64; 11],
+    sum_seconds: f64,
+}
+
+/// Requests handled by method, route and status, managed when `metrics_enabled` is set.
+#[derive(Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String, u16), RequestStats>>,
+}
+
+impl Metrics {
+    pub fn record_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let mut requests = match self.requests.lock() {
+            Ok(requests) => requests,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let stats = requests
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert_with(RequestStats::default);
+        stats.count += 1;
+        stats.sum_seconds += seconds;
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound) {
+            stats.buckets[index] += 1;
+        }
+    }
+
+    /// Everything recorded, with the current state of the pools, in the Prometheus text format.
+    pub fn render(
+        &self,
+        pool: &Pool<ConnectionManager<DbConnection>>,
+        database_pools: &DatabasePools,
+    ) -> String {
+        let mut out = String::new();
+        self.render_requests(&mut out);
+        render_pools(&mut out, pool, database_pools);
+        render_mails(&mut out);
+        out
+    }
+
+    fn render_requests(&self, out: &mut String) {
+        let requests = match self.requests.lock() {
+            Ok(requests) => requests,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        help(out, \"http_requests_total\", \"counter\", \"Requests handled.\");
+        for (&(ref method, ref route, status), stats) in requests.iter() {
+            let status = status.to_string();
+            let labels = [(\"method\", method.as_str()), (\"route\", route.as_str()), (\"status\", status.as_str())];
+            sample(out, \"http_requests_total\", &labels, &stats.count.to_string());
+        }
+
+        help(out, \"http_request_duration_seconds\", \"histogram\", \"Time to handle a request.\");
+        for (&(ref method, ref route, status), stats) in requests.iter() {
+            let status = status.to_string();
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(stats.buckets.iter()) {
+                cumulative += count;
+                let bound = bound.to_string();
+                let labels = [
+                    (\"method\", method.as_str()),
+                    (\"route\", route.as_str()),
+                    (\"status\", status.as_str()),
+                    (\"le\", bound.as_str()),
+                ];
+                sample(out, \"http_request_duration_seconds_bucket\", &labels, &cumulative.to_string());
+            }
+            let labels = [
+                (\"method\", method.as_str()),
+                (\"route\", route.as_str()),
+                (\"status\", status.as_str()),
+                (\"le\", \"+Inf\"),
+            ];
+            sample(out, \"http_request_duration_seconds_bucket\", &labels, &stats.count.to_string());
+            let labels = [(\"method\", method.as_str()), (\"route\", route.as_str()), (\"status\", status.as_str())];
+            sample(out, \"http_request_duration_seconds_sum\", &labels, &stats.sum_seconds.to_string());
+            sample(out, \"http_request_duration_seconds_count\", &labels, &stats.count.to_string());
+        }
+    }
+}
+
+/// Record the request, by the path of its route so ids in the url don't make a series each.
+pub fn on_response(request: &Request, response: &Response) {
+    let metrics = match request.guard::<State<Metrics>>() {
+        Outcome::Success(metrics) => metrics,
+        _ => return,
+    };
+    let route = request
+        .route()
+        .map_or_else(|| String::from(\"unmatched\"), |route| route.uri.path().to_string());
+    metrics.record_request(
+        request.method().as_str(),
+        &route,
+        response.status().code,
+        request_log::elapsed_seconds(),
+    );
+}
+
+/// Count the result of sending an email.
+pub fn record_mail<T, E>(result: &Result<T, E>) {
+    match *result {
+        Ok(_) => MAILS_SENT.fetch_add(1, Ordering::Relaxed),
+        Err(_) => MAILS_FAILED.fetch_add(1, Ordering::Relaxed),
+    };
+}
+
+/// Lets the request through when `metrics_allowed_ips` is empty or contains the client IP,
+/// fails with 403 otherwise.
+pub struct MetricsAccess;
+
+impl<'a, 'r> FromRequest<'a, 'r> for MetricsAccess {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<MetricsAccess, ()> {
+        let settings = request.guard::<State<Settings>>()?;
+        let allowed_ips = &settings.metrics.allowed_ips;
+        if allowed_ips.is_empty() {
+            return Outcome::Success(MetricsAccess);
+        }
+        let client = request.remote().map(|address| address.ip());
+        let allowed = allowed_ips
+            .iter()
+            .filter_map(|ip| ip.parse::<IpAddr>().ok())
+            .any(|ip| Some(ip) == client);
+        if allowed {
+            Outcome::Success(MetricsAccess)
+        } else {
+            Outcome::Failure((Status::Forbidden, ()))
+        }
+    }
+}
+
+// The primary pool and the named ones, by `pool` label. A named pool called `primary`
+// would repeat the label of the primary one, so it is left out
+fn render_pools(
+    out: &mut String,
+    pool: &Pool<ConnectionManager<DbConnection>>,
+    database_pools: &DatabasePools,
+) {
+    let mut pools = vec![(PRIMARY_POOL, pool)];
+    for name in database_pools.names() {
+        if name == PRIMARY_POOL {
+            continue;
+        }
+        if let Some(named_pool) = database_pools.get(name) {
+            pools.push((name, named_pool));
+        }
+    }
+
+    help(out, \"db_pool_connections\", \"gauge\", \"Connections opened by the pool.\");
+    for &(name, pool) in &pools {
+        sample(out, \"db_pool_connections\", &[(\"pool\", name)], &pool.state().connections.to_string());
+    }
+    help(out, \"db_pool_idle_connections\", \"gauge\", \"Connections waiting to be used.\");
+    for &(name, pool) in &pools {
+        let idle_connections = pool.state().idle_connections.to_string();
+        sample(out, \"db_pool_idle_connections\", &[(\"pool\", name)], &idle_connections);
+    }
+    help(out, \"db_pool_max_connections\", \"gauge\", \"Maximum size of the pool.\");
+    for &(name, pool) in &pools {
+        sample(out, \"db_pool_max_connections\", &[(\"pool\", name)], &pool.max_size().to_string());
+    }
+}
+
+fn render_mails(out: &mut String) {
+    let sent = MAILS_SENT.load(Ordering::Relaxed).to_string();
+    let failed = MAILS_FAILED.load(Ordering::Relaxed).to_string();
+    help(out, \"mailer_sends_total\", \"counter\", \"Emails sent, by result.\");
+    sample(out, \"mailer_sends_total\", &[(\"result\", \"success\")], &sent);
+    sample(out, \"mailer_sends_total\", &[(\"result\", \"failure\")], &failed);
+}
+
+fn help(out: &mut String, name: &str, kind: &str, help: &str) {
+    out.push_str(&format!(\"# HELP {} {}\\n# TYPE {} {}\\n\", name, help, name, kind));
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: &str) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|&(label, value)| format!(\"{}=\\\"{}\\\"\", label, escape_label(value)))
+            .collect();
+        out.push('{');
+        out.push_str(&labels.join(\",\"));
+        out.push('}');
+    }
+    out.push(' ');
+    out.push_str(value);
+    out.push('\\n');
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\\\', \"\\\\\\\\\")
+        .replace('\"', \"\\\\\\\"\")
+        .replace('\\n', \"\\\\n\")
+}
+";
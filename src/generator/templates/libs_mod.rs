@@ -1,10 +1,24 @@
 pub static TEXT: &'static str = "pub mod conn;
+pub mod csrf;
 pub mod db;
+pub mod env_overrides;
 pub mod init;
 pub mod lib_const;
 pub mod log_format;
+pub mod log_rotation;
 pub mod logger;
+pub mod mailer_config;
+pub mod metrics;
+pub mod password;
+pub mod rate_limit;
+pub mod request_log;
 pub mod routes;
 pub mod settings;
+pub mod settings_validation;
+pub mod token;
+pub mod totp;
 #[cfg(feature = \"email\")]
+pub mod email;
+#[cfg(not(feature = \"email\"))]
+#[path = \"email_disabled.rs\"]
 pub mod email;";
@@ -0,0 +1,47 @@
+pub static TEXT: &'static str = "use argon2rs::{argon2i_simple, Argon2, Variant};
+use argon2rs::verifier::{constant_eq, Encoded};
+use rand::{OsRng, Rng};
+
+use super::settings::ApplicationSecurity;
+
+const SALT_LENGTH: usize = 16;
+
+/// Hash `password` with a random salt into a PHC string:
+/// `$argon2i$m=<memory_kib>,t=<passes>,p=<lanes>$<salt>$<hash>`
+pub fn hash_password(password: &str, security: &ApplicationSecurity) -> String {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut rand_gen = OsRng::new().expect(\"Couldn't make OsRng!\");
+    rand_gen.fill_bytes(&mut salt);
+    let encoded = Encoded::new(hasher(security), password.as_bytes(), &salt, &[], &[]);
+    String::from_utf8(encoded.to_u8()).expect(\"PHC string is always ASCII\")
+}
+
+/// Verify `password` against a PHC string, in constant time.
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    match Encoded::from_u8(password_hash.as_bytes()) {
+        Ok(encoded) => encoded.verify(password.as_bytes()),
+        Err(_) => false,
+    }
+}
+
+/// Verify `password` against a hash made with the global `password_salt`, before per-user salts.
+pub fn verify_legacy_password(password: &str, salt: &str, encrypted_password: &[u8]) -> bool {
+    constant_eq(&argon2i_simple(password, salt), encrypted_password)
+}
+
+/// Whether `password_hash` was made with different parameters than the current settings.
+pub fn needs_rehash(password_hash: &str, security: &ApplicationSecurity) -> bool {
+    let (_, memory_kib, passes, lanes) = hasher(security).params();
+    let prefix = format!(\"$argon2i$m={},t={},p={}$\", memory_kib, passes, lanes);
+    !password_hash.starts_with(&prefix)
+}
+
+fn hasher(security: &ApplicationSecurity) -> Argon2 {
+    Argon2::new(
+        security.password_hash_passes,
+        security.password_hash_lanes,
+        security.password_hash_memory_kib,
+        Variant::Argon2i,
+    ).unwrap_or_else(|_| Argon2::default(Variant::Argon2i))
+}
+";
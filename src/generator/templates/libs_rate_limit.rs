@@ -0,0 +1,214 @@
+pub static TEXT: &'static str = "use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use diesel;
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error};
+use r2d2::Pool;
+use r2d2_diesel::ConnectionManager;
+
+use rocket::http::Status;
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request, State};
+
+use schema::rate_limits;
+use super::db::DbConnection;
+use super::settings::{RateLimitRule, RateLimits};
+
+// Past this size the memory store drops its expired windows
+const MEMORY_STORE_PRUNE_SIZE: usize = 10_000;
+
+/// Seconds until the rejected request may be retried, cached on the request for the 429 catcher.
+struct RetryAfterSeconds(i64);
+
+/// Where hits are counted, `rate_limit_store` in `Rocket.toml`.
+pub trait RateLimitStore: Send + Sync {
+    /// Count a hit on `bucket` and return its hits and the end of its window,
+    /// a new window is started when the current one ended before `now`.
+    fn hit(&self, bucket: &str, now: i64, window_seconds: i64) -> Result<(u32, i64), String>;
+}
+
+/// Counts in the process, for a single instance.
+#[derive(Default)]
+pub struct MemoryStore {
+    windows: Mutex<HashMap<String, (u32, i64)>>,
+}
+
+impl RateLimitStore for MemoryStore {
+    fn hit(&self, bucket: &str, now: i64, window_seconds: i64) -> Result<(u32, i64), String> {
+        let mut windows = self.windows.lock().map_err(|e| e.to_string())?;
+        if windows.len() > MEMORY_STORE_PRUNE_SIZE {
+            windows.retain(|_, &mut (_, window_end)| window_end > now);
+        }
+        let window = windows
+            .entry(bucket.to_string())
+            .or_insert((0, now + window_seconds));
+        if window.1 <= now {
+            *window = (0, now + window_seconds);
+        }
+        window.0 += 1;
+        Ok(*window)
+    }
+}
+
+/// Counts in the `rate_limits` table, shared by every instance.
+pub struct DatabaseStore {
+    pool: Pool<ConnectionManager<DbConnection>>,
+}
+
+impl DatabaseStore {
+    pub fn new(pool: Pool<ConnectionManager<DbConnection>>) -> Self {
+        DatabaseStore { pool }
+    }
+}
+
+impl RateLimitStore for DatabaseStore {
+    fn hit(&self, bucket: &str, now: i64, window_seconds: i64) -> Result<(u32, i64), String> {
+        let conn = self.pool.get().map_err(|e| e.to_string())?;
+        // A concurrent request inserted the bucket first, the hit is counted on its row
+        let window = match hit_bucket(&conn, bucket, now, window_seconds) {
+            Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                hit_bucket(&conn, bucket, now, window_seconds)
+            }
+            window => window,
+        };
+        window.map_err(|e| e.to_string())
+    }
+}
+
+// Every statement counts the hit by itself, so concurrent requests can't overwrite each
+// other, and the row is read back once updated
+fn hit_bucket(
+    conn: &DbConnection,
+    bucket: &str,
+    now: i64,
+    window_seconds: i64,
+) -> Result<(u32, i64), Error> {
+    conn.transaction::<_, Error, _>(|| {
+        let counted = diesel::update(
+            rate_limits::table
+                .filter(rate_limits::bucket.eq(bucket))
+                .filter(rate_limits::window_end.gt(now)),
+        ).set(rate_limits::hits.eq(rate_limits::hits + 1))
+            .execute(conn)?;
+        if counted == 0 {
+            let restarted = diesel::update(
+                rate_limits::table
+                    .filter(rate_limits::bucket.eq(bucket))
+                    .filter(rate_limits::window_end.le(now)),
+            ).set((
+                rate_limits::hits.eq(1),
+                rate_limits::window_end.eq(now + window_seconds),
+            ))
+                .execute(conn)?;
+            if restarted == 0 {
+                diesel::insert_into(rate_limits::table)
+                    .values((
+                        rate_limits::bucket.eq(bucket),
+                        rate_limits::hits.eq(1),
+                        rate_limits::window_end.eq(now + window_seconds),
+                    ))
+                    .execute(conn)?;
+            }
+        }
+        let (hits, window_end) = rate_limits::table
+            .find(bucket)
+            .select((rate_limits::hits, rate_limits::window_end))
+            .first::<(i32, i64)>(conn)?;
+        Ok((hits as u32, window_end))
+    })
+}
+
+/// The configured groups and their store, managed by Rocket.
+pub struct RateLimiter {
+    store: Box<RateLimitStore>,
+    groups: BTreeMap<String, RateLimitRule>,
+}
+
+impl RateLimiter {
+    pub fn new(rate_limits: &RateLimits, pool: &Pool<ConnectionManager<DbConnection>>) -> Self {
+        let store: Box<RateLimitStore> = match rate_limits.store.as_ref() {
+            \"database\" => Box::new(DatabaseStore::new(pool.clone())),
+            _ => Box::new(MemoryStore::default()),
+        };
+        RateLimiter {
+            store,
+            groups: rate_limits.groups.clone(),
+        }
+    }
+
+    /// `Err` with the seconds to wait when `client` made too many requests to `group`.
+    pub fn check(&self, group: &str, client: &str) -> Result<(), i64> {
+        let rule = match self.groups.get(group) {
+            Some(rule) => rule,
+            None => return Ok(()),
+        };
+        let now = Utc::now().timestamp();
+        let bucket = format!(\"{}:{}\", group, client);
+        match self.store.hit(&bucket, now, i64::from(rule.window_seconds)) {
+            Ok((hits, window_end)) if hits > rule.requests => Err(window_end - now),
+            // A failing store doesn't lock everybody out
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A group of routes sharing a limit, from `rate_limits` in `Rocket.toml`.
+pub trait RateLimitGroup {
+    const NAME: &'static str;
+}
+
+pub struct LoginLimit;
+
+impl RateLimitGroup for LoginLimit {
+    const NAME: &'static str = \"login\";
+}
+
+pub struct RegisterLimit;
+
+impl RateLimitGroup for RegisterLimit {
+    const NAME: &'static str = \"register\";
+}
+
+pub struct TokenLimit;
+
+impl RateLimitGroup for TokenLimit {
+    const NAME: &'static str = \"tokens\";
+}
+
+/// Counts the request against the limit of `G` per client IP, failing with 429 past it.
+/// Put it before the other guards, so rejected requests stay cheap.
+///
+/// The client IP is taken from `X-Real-IP` when present, as set by a reverse proxy,
+/// otherwise from the connection. Without a proxy overwriting it, clients can send their
+/// own `X-Real-IP` to get a new limit on every request.
+pub struct RateLimit<G: RateLimitGroup>(PhantomData<G>);
+
+impl<'a, 'r, G: RateLimitGroup> FromRequest<'a, 'r> for RateLimit<G> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<RateLimit<G>, ()> {
+        let rate_limiter = request.guard::<State<RateLimiter>>()?;
+        let client = request
+            .real_ip()
+            .or_else(|| request.remote().map(|address| address.ip()))
+            .map(|ip| ip.to_string())
+            .unwrap_or_default();
+
+        match rate_limiter.check(G::NAME, &client) {
+            Ok(_) => Outcome::Success(RateLimit(PhantomData)),
+            Err(retry_after) => {
+                request.local_cache(|| RetryAfterSeconds(retry_after));
+                Outcome::Failure((Status::TooManyRequests, ()))
+            }
+        }
+    }
+}
+
+/// Seconds to wait before retrying `request`, once rejected by a `RateLimit`.
+pub fn retry_after(request: &Request) -> i64 {
+    request.local_cache(|| RetryAfterSeconds(0)).0
+}
+";
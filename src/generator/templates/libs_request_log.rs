@@ -0,0 +1,137 @@
+pub static TEXT: &'static str = "use std::cell::{Cell, RefCell};
+use std::ops::Deref;
+use std::time::Instant;
+
+use uuid::Uuid;
+
+use rocket::http::Header;
+use rocket::request::{self, FromRequest};
+use rocket::{Outcome, Request, Response, State};
+
+use super::log_format::{AccessContext, AccessLog};
+use super::logger::{AccessEvent, CombinedLogger};
+use super::settings::Settings;
+use models::user::User;
+
+pub const REQUEST_ID_HEADER: &str = \"X-Request-Id\";
+const REQUEST_ID_MAX_LENGTH: usize = 128;
+
+thread_local! {
+    /// Start of the request being handled, set by the request fairing for the latency.
+    static STARTED_AT: Cell<Option<Instant>> = Cell::new(None);
+    /// Id and email of the user of the request being handled, set by the user guards.
+    static USER: RefCell<Option<(i32, String)>> = RefCell::new(None);
+}
+
+/// Keep the `X-Request-Id` of a proxy when it looks sane, otherwise generate one,
+/// so the guards and the response fairing see the same id.
+pub fn on_request(request: &mut Request) {
+    STARTED_AT.with(|started_at| started_at.set(Some(Instant::now())));
+    USER.with(|user| *user.borrow_mut() = None);
+
+    let request_id = match request.headers().get_one(REQUEST_ID_HEADER) {
+        Some(request_id) if is_valid_request_id(request_id) => request_id.to_string(),
+        _ => Uuid::new_v4().to_string(),
+    };
+    request.replace_header(Header::new(REQUEST_ID_HEADER, request_id));
+}
+
+/// Echo the request id and log the request with the `access_log_format` of the settings.
+pub fn on_response(request: &Request, response: &mut Response) {
+    let request_id = request_id(request);
+    response.set_raw_header(REQUEST_ID_HEADER, request_id.clone());
+
+    let (logger, settings) = match (
+        request.guard::<State<CombinedLogger>>(),
+        request.guard::<State<Settings>>(),
+    ) {
+        (Outcome::Success(logger), Outcome::Success(settings)) => (logger, settings),
+        _ => return,
+    };
+    let latency_ms = elapsed_seconds() * 1000.0;
+    let user = USER.with(|user| user.borrow().clone());
+    let user_id = user.as_ref().map(|&(user_id, _)| user_id);
+
+    let context = AccessContext {
+        request_id: &request_id,
+        user_id,
+        user: user.as_ref().map(|&(_, ref email)| email.as_str()),
+        latency_ms,
+    };
+    let line = AccessLog::log(
+        &settings.our_logger.access_log_format,
+        request,
+        response,
+        &context,
+    );
+    let event = AccessEvent {
+        request_id: &request_id,
+        method: request.method().as_str(),
+        path: request.uri().path(),
+        status: response.status().code,
+        latency_ms,
+        user_id,
+    };
+    logger.access(&line, &event);
+}
+
+/// Time spent on the request being handled, 0 outside of a request.
+pub fn elapsed_seconds() -> f64 {
+    STARTED_AT.with(|started_at| started_at.get()).map_or(0.0, |started_at| {
+        let elapsed = started_at.elapsed();
+        elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1_000_000_000.0
+    })
+}
+
+/// Record the signed in user of the request for its log event.
+pub fn set_user(user: &User) {
+    USER.with(|current| *current.borrow_mut() = Some((user.id, user.email.clone())));
+}
+
+/// Id of the request, as set by the request fairing.
+#[derive(Debug)]
+pub struct RequestId(pub String);
+
+impl<'a, 'r> FromRequest<'a, 'r> for RequestId {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<RequestId, ()> {
+        Outcome::Success(RequestId(request_id(request)))
+    }
+}
+
+/// The loggers with the request id added to every event, for logging from the handlers.
+pub struct RequestLogger(CombinedLogger);
+
+impl<'a, 'r> FromRequest<'a, 'r> for RequestLogger {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<RequestLogger, ()> {
+        let logger = request.guard::<State<CombinedLogger>>()?;
+        Outcome::Success(RequestLogger(logger.with_request_id(&request_id(request))))
+    }
+}
+
+impl Deref for RequestLogger {
+    type Target = CombinedLogger;
+
+    fn deref(&self) -> &CombinedLogger {
+        &self.0
+    }
+}
+
+fn request_id(request: &Request) -> String {
+    request
+        .headers()
+        .get_one(REQUEST_ID_HEADER)
+        .unwrap_or(\"-\")
+        .to_string()
+}
+
+fn is_valid_request_id(request_id: &str) -> bool {
+    !request_id.is_empty() && request_id.len() <= REQUEST_ID_MAX_LENGTH
+        && request_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+";
@@ -1,15 +1,28 @@
 pub static TEXT: &'static str = "use rocket::{Rocket, Route};
-use controllers::{pages_controller, users_controller};
+use super::csrf;
+use apis::{health_api, tokens_api};
+use controllers::{
+    confirmations_controller, pages_controller, password_resets_controller, sessions_controller,
+    two_factor_controller, unlocks_controller, users_controller,
+};
 
 pub fn routes(attached_rocket: Rocket) -> Rocket {
     attached_rocket
         .mount(\"/\", root_urls())
         .mount(\"/users\", user_urls())
+        .mount(\"/password_resets\", password_reset_urls())
+        .mount(\"/confirmations\", confirmation_urls())
+        .mount(\"/sessions\", session_urls())
+        .mount(\"/unlocks\", unlock_urls())
+        .mount(\"/two_factor\", two_factor_urls())
+        .mount(\"/apis/tokens\", token_urls())
+        .mount(\"/health\", health_urls())
 }
 
 fn root_urls() -> Vec<Route> {
     routes![
         pages_controller::index,
+        csrf::failure,
     ]
 }
 
@@ -24,4 +37,62 @@ fn user_urls() -> Vec<Route> {
         users_controller::register,
     ]
 }
+
+fn password_reset_urls() -> Vec<Route> {
+    routes![
+        password_resets_controller::new,
+        password_resets_controller::create,
+        password_resets_controller::edit,
+        password_resets_controller::update,
+    ]
+}
+
+fn confirmation_urls() -> Vec<Route> {
+    routes![
+        confirmations_controller::new,
+        confirmations_controller::create,
+        confirmations_controller::confirm,
+    ]
+}
+
+fn session_urls() -> Vec<Route> {
+    routes![
+        sessions_controller::failed_index,
+        sessions_controller::index,
+        sessions_controller::revoke,
+        sessions_controller::revoke_all,
+    ]
+}
+
+fn unlock_urls() -> Vec<Route> {
+    routes![
+        unlocks_controller::unlock,
+    ]
+}
+
+fn two_factor_urls() -> Vec<Route> {
+    routes![
+        two_factor_controller::failed_new,
+        two_factor_controller::new,
+        two_factor_controller::enable,
+        two_factor_controller::failed_verify,
+        two_factor_controller::verify,
+        two_factor_controller::authenticate,
+        two_factor_controller::disable,
+    ]
+}
+
+fn token_urls() -> Vec<Route> {
+    routes![
+        tokens_api::create,
+        tokens_api::delete,
+    ]
+}
+
+fn health_urls() -> Vec<Route> {
+    routes![
+        health_api::live,
+        health_api::ready,
+    ]
+}
 ";
@@ -1,13 +1,33 @@
 pub static TEXT: &'static str = "use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time;
 
 use chrono::Duration;
+use r2d2::{Builder, ManageConnection, Pool};
 use sloggers::types::Severity;
 
 use rocket::config::{Config, Value};
 
+use super::log_format::resolve_format;
+use super::log_rotation::{RotateInterval, RotationPolicy};
+
 pub struct ApplicationSecurity {
     pub access_token_timeout_days: u32,
     pub password_salt: String,
+    pub password_hash_passes: u32,
+    pub password_hash_lanes: u32,
+    pub password_hash_memory_kib: u32,
+    pub password_reset_timeout_hours: u32,
+    pub token_secret: String,
+    pub require_email_confirmation: bool,
+    pub email_confirmation_timeout_hours: u32,
+    pub session_idle_timeout_minutes: u32,
+    pub session_absolute_timeout_days: u32,
+    pub activity_refresh_seconds: u32,
+    pub lockout_max_failed_attempts: u32,
+    pub lockout_duration_minutes: u32,
+    pub two_factor_issuer: String,
+    pub two_factor_required_roles: Vec<String>,
 }
 
 pub struct Assets {
@@ -16,16 +36,102 @@ pub struct Assets {
     pub serve_assets: bool,
 }
 
+/// A pool of `databases`, like `replica`, sharing the other settings of the primary one.
+#[derive(Clone)]
+pub struct NamedDatabase {
+    pub database_url: String,
+    pub database_pool: u32,
+    /// Its own `database_min_idle`, else the primary one, at most `database_pool`.
+    pub database_min_idle: Option<u32>,
+}
+
 pub struct Database {
     pub database_url: String,
     pub database_pool: u32,
+    /// Pools besides the primary one, which `databases.primary` replaces if set.
+    pub databases: BTreeMap<String, NamedDatabase>,
+    /// Connections kept open when idle, `None` to keep `database_pool` of them.
+    pub database_min_idle: Option<u32>,
+    pub database_connection_timeout_seconds: u64,
+    /// Idle connections are closed after this long, 0 to keep them.
+    pub database_idle_timeout_seconds: u64,
+    /// Connections are closed after this long, 0 to keep them.
+    pub database_max_lifetime_seconds: u64,
+    pub database_test_on_check_out: bool,
+    /// Attempts to reach the database at startup after the first one,
+    /// waiting `database_connect_backoff_ms` doubled each time.
+    pub database_connect_retries: u32,
+    pub database_connect_backoff_ms: u64,
+}
+
+impl Database {
+    pub fn pool_builder<M: ManageConnection>(&self) -> Builder<M> {
+        Pool::builder()
+            .max_size(self.database_pool)
+            .min_idle(self.database_min_idle)
+            .connection_timeout(time::Duration::from_secs(self.database_connection_timeout_seconds))
+            .idle_timeout(optional_seconds(self.database_idle_timeout_seconds))
+            .max_lifetime(optional_seconds(self.database_max_lifetime_seconds))
+            .test_on_check_out(self.database_test_on_check_out)
+    }
+
+    pub fn connect_backoff(&self) -> time::Duration {
+        time::Duration::from_millis(self.database_connect_backoff_ms)
+    }
+}
+
+/// `text` lines for reading, or one `json` object per event for log collectors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LogFormat {
+    Text,
+    Json,
 }
 
 pub struct OurLogger {
     pub terminal_logger: bool,
     pub file_logger: bool,
-    pub file_logger_dir_path: String,
+    pub file_logger_dir_path: PathBuf,
+    pub log_rotate_size_mb: u64,
+    pub log_rotate_interval: RotateInterval,
+    pub log_rotate_keep: usize,
+    pub log_rotate_compress: bool,
     pub log_level: Severity,
+    pub log_format: LogFormat,
+    pub access_log_format: String,
+}
+
+impl OurLogger {
+    pub fn rotation_policy(&self) -> RotationPolicy {
+        RotationPolicy {
+            max_size_bytes: self.log_rotate_size_mb * 1024 * 1024,
+            interval: self.log_rotate_interval,
+            keep: self.log_rotate_keep,
+            compress: self.log_rotate_compress,
+        }
+    }
+}
+
+/// Prometheus metrics served at `path`, to every client when `allowed_ips` is empty.
+pub struct Metrics {
+    pub enabled: bool,
+    pub path: String,
+    pub allowed_ips: Vec<String>,
+}
+
+pub struct Views {
+    pub debug_context: bool,
+    pub template_dir: PathBuf,
+}
+
+#[derive(Clone)]
+pub struct RateLimitRule {
+    pub requests: u32,
+    pub window_seconds: u32,
+}
+
+pub struct RateLimits {
+    pub store: String,
+    pub groups: BTreeMap<String, RateLimitRule>,
 }
 
 pub struct Settings {
@@ -33,27 +139,84 @@ pub struct Settings {
     pub assets: Assets,
     pub database: Database,
     pub our_logger: OurLogger,
+    pub metrics: Metrics,
+    pub views: Views,
+    pub rate_limits: RateLimits,
 }
 
 impl ApplicationSecurity {
     pub fn access_token_duration(&self) -> Duration {
         Duration::days(i64::from(self.access_token_timeout_days))
     }
+
+    pub fn password_reset_duration(&self) -> Duration {
+        Duration::hours(i64::from(self.password_reset_timeout_hours))
+    }
+
+    pub fn email_confirmation_duration(&self) -> Duration {
+        Duration::hours(i64::from(self.email_confirmation_timeout_hours))
+    }
+
+    pub fn session_idle_duration(&self) -> Duration {
+        Duration::minutes(i64::from(self.session_idle_timeout_minutes))
+    }
+
+    pub fn session_absolute_duration(&self) -> Duration {
+        Duration::days(i64::from(self.session_absolute_timeout_days))
+    }
+
+    pub fn activity_refresh_duration(&self) -> Duration {
+        Duration::seconds(i64::from(self.activity_refresh_seconds))
+    }
+
+    pub fn lockout_duration(&self) -> Duration {
+        Duration::minutes(i64::from(self.lockout_duration_minutes))
+    }
 }
 
 impl Settings {
     pub fn new_from_config(config: &Config) -> Self {
         let default_access_token_timeout_days = 30;
         let default_password_salt = \"somerandomsalt\";
+        let default_password_hash_passes = 3;
+        let default_password_hash_lanes = 1;
+        let default_password_hash_memory_kib = 4096;
+        let default_password_reset_timeout_hours = 2;
+        let default_token_secret = \"somerandomsecret\";
+        let default_require_email_confirmation = true;
+        let default_email_confirmation_timeout_hours = 48;
+        let default_session_idle_timeout_minutes = 120;
+        let default_session_absolute_timeout_days = 30;
+        let default_activity_refresh_seconds = 60;
+        let default_lockout_max_failed_attempts = 5;
+        let default_lockout_duration_minutes = 30;
+        let default_two_factor_issuer = \"{{name}}\";
         let default_assets_dir = \"src/assets\";
         let default_assets_host = \"localhost:8000\";
         let default_serve_assets = true;
         let default_database_url = \"\";
         let default_database_pool = 5;
+        let default_database_connection_timeout_seconds = 30;
+        let default_database_idle_timeout_seconds = 600;
+        let default_database_max_lifetime_seconds = 1800;
+        let default_database_test_on_check_out = true;
+        let default_database_connect_retries = 5;
+        let default_database_connect_backoff_ms = 500;
         let default_terminal_logger = true;
         let default_file_logger = true;
         let default_file_logger_dir_path = \"log/\";
+        let default_log_rotate_size_mb = 0;
+        let default_log_rotate_interval = \"never\";
+        let default_log_rotate_keep = 7;
+        let default_log_rotate_compress = false;
         let default_log_level = \"debug\";
+        let default_log_format = \"text\";
+        let default_access_log_format = \"common\";
+        let default_metrics_enabled = false;
+        let default_metrics_path = \"/metrics\";
+        let default_debug_context = true;
+        let default_template_dir = \"templates/\";
+        let default_rate_limit_store = \"memory\";
 
         let access_token_timeout_days = config
             .get_int(\"access_token_timeout_days\")
@@ -62,6 +225,61 @@ impl Settings {
         let password_salt = config
             .get_string(\"password_salt\")
             .unwrap_or_else(|_| default_password_salt.to_string());
+        let password_hash_passes = config
+            .get_int(\"password_hash_passes\")
+            .unwrap_or(default_password_hash_passes) as u32;
+        let password_hash_lanes = config
+            .get_int(\"password_hash_lanes\")
+            .unwrap_or(default_password_hash_lanes) as u32;
+        let password_hash_memory_kib = config
+            .get_int(\"password_hash_memory_kib\")
+            .unwrap_or(default_password_hash_memory_kib) as u32;
+        let password_reset_timeout_hours = config
+            .get_int(\"password_reset_timeout_hours\")
+            .unwrap_or(default_password_reset_timeout_hours)
+            as u32;
+        let token_secret = config
+            .get_string(\"token_secret\")
+            .unwrap_or_else(|_| default_token_secret.to_string());
+        let require_email_confirmation = config
+            .get_bool(\"require_email_confirmation\")
+            .unwrap_or(default_require_email_confirmation);
+        let email_confirmation_timeout_hours = config
+            .get_int(\"email_confirmation_timeout_hours\")
+            .unwrap_or(default_email_confirmation_timeout_hours)
+            as u32;
+        let session_idle_timeout_minutes = config
+            .get_int(\"session_idle_timeout_minutes\")
+            .unwrap_or(default_session_idle_timeout_minutes)
+            as u32;
+        let session_absolute_timeout_days = config
+            .get_int(\"session_absolute_timeout_days\")
+            .unwrap_or(default_session_absolute_timeout_days)
+            as u32;
+        let activity_refresh_seconds = config
+            .get_int(\"activity_refresh_seconds\")
+            .unwrap_or(default_activity_refresh_seconds)
+            as u32;
+        let lockout_max_failed_attempts = config
+            .get_int(\"lockout_max_failed_attempts\")
+            .unwrap_or(default_lockout_max_failed_attempts)
+            as u32;
+        let lockout_duration_minutes = config
+            .get_int(\"lockout_duration_minutes\")
+            .unwrap_or(default_lockout_duration_minutes)
+            as u32;
+        let two_factor_issuer = config
+            .get_string(\"two_factor_issuer\")
+            .unwrap_or_else(|_| default_two_factor_issuer.to_string());
+        let two_factor_required_roles = config
+            .get_slice(\"two_factor_required_roles\")
+            .map(|roles| {
+                roles
+                    .iter()
+                    .filter_map(|role| role.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_else(|_| Vec::new());
         let assets_dir = config
             .get_string(\"assets_dir\")
             .unwrap_or_else(|_| default_assets_dir.to_string());
@@ -77,20 +295,122 @@ impl Settings {
         let database_pool = config
             .get_int(\"database_pool\")
             .unwrap_or(default_database_pool) as u32;
+        let database_min_idle = config
+            .get_int(\"database_min_idle\")
+            .ok()
+            .map(|min_idle| min_idle as u32);
+        let mut databases = config
+            .get_table(\"databases\")
+            .map(|databases| parse_databases(databases, database_pool, database_min_idle))
+            .unwrap_or_else(|_| BTreeMap::new());
+        let (database_url, database_pool, database_min_idle) = match databases.remove(\"primary\") {
+            Some(primary) => (
+                primary.database_url,
+                primary.database_pool,
+                primary.database_min_idle,
+            ),
+            None => (database_url, database_pool, database_min_idle),
+        };
+        let database_connection_timeout_seconds = config
+            .get_int(\"database_connection_timeout_seconds\")
+            .unwrap_or(default_database_connection_timeout_seconds)
+            as u64;
+        let database_idle_timeout_seconds = config
+            .get_int(\"database_idle_timeout_seconds\")
+            .unwrap_or(default_database_idle_timeout_seconds)
+            as u64;
+        let database_max_lifetime_seconds = config
+            .get_int(\"database_max_lifetime_seconds\")
+            .unwrap_or(default_database_max_lifetime_seconds)
+            as u64;
+        let database_test_on_check_out = config
+            .get_bool(\"database_test_on_check_out\")
+            .unwrap_or(default_database_test_on_check_out);
+        let database_connect_retries = config
+            .get_int(\"database_connect_retries\")
+            .unwrap_or(default_database_connect_retries)
+            as u32;
+        let database_connect_backoff_ms = config
+            .get_int(\"database_connect_backoff_ms\")
+            .unwrap_or(default_database_connect_backoff_ms)
+            as u64;
         let terminal_logger = config
             .get_bool(\"terminal_logger\")
             .unwrap_or(default_terminal_logger);
         let file_logger = config
             .get_bool(\"file_logger\")
             .unwrap_or(default_file_logger);
+        // Relative to the project root, where `Rocket.toml` is
         let file_logger_dir_path = config
-            .get_string(\"file_logger_dir_path\")
-            .unwrap_or_else(|_| default_file_logger_dir_path.to_string());
+            .get_str(\"file_logger_dir_path\")
+            .map(|path| config.root_relative(path))
+            .unwrap_or_else(|_| config.root_relative(default_file_logger_dir_path));
+        let log_rotate_size_mb = config
+            .get_int(\"log_rotate_size_mb\")
+            .unwrap_or(default_log_rotate_size_mb) as u64;
+        let log_rotate_interval = config
+            .get_str(\"log_rotate_interval\")
+            .unwrap_or(default_log_rotate_interval);
+        let log_rotate_keep = config
+            .get_int(\"log_rotate_keep\")
+            .unwrap_or(default_log_rotate_keep) as usize;
+        let log_rotate_compress = config
+            .get_bool(\"log_rotate_compress\")
+            .unwrap_or(default_log_rotate_compress);
         let log_level = config.get_str(\"log_level\").unwrap_or(default_log_level);
+        let log_format = config.get_str(\"log_format\").unwrap_or(default_log_format);
+        let access_log_format = config
+            .get_str(\"access_log_format\")
+            .unwrap_or(default_access_log_format);
+        let metrics_enabled = config
+            .get_bool(\"metrics_enabled\")
+            .unwrap_or(default_metrics_enabled);
+        let metrics_path = config
+            .get_string(\"metrics_path\")
+            .unwrap_or_else(|_| default_metrics_path.to_string());
+        let metrics_allowed_ips = config
+            .get_slice(\"metrics_allowed_ips\")
+            .map(|ips| {
+                ips.iter()
+                    .filter_map(|ip| ip.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_else(|_| Vec::new());
+        let debug_context = config
+            .get_bool(\"debug_context\")
+            .unwrap_or(default_debug_context);
+        // The key read by the `Template` fairing, relative to the project root
+        let template_dir = config
+            .get_str(\"template_dir\")
+            .map(|path| config.root_relative(path))
+            .unwrap_or_else(|_| config.root_relative(default_template_dir));
+        // Like the other choices, `memory` or `database` in any case
+        let rate_limit_store = config
+            .get_string(\"rate_limit_store\")
+            .unwrap_or_else(|_| default_rate_limit_store.to_string())
+            .to_lowercase();
+        let rate_limit_groups = config
+            .get_table(\"rate_limits\")
+            .map(parse_rate_limits)
+            .unwrap_or_else(|_| BTreeMap::new());
 
         let application_security = ApplicationSecurity {
             access_token_timeout_days,
             password_salt,
+            password_hash_passes,
+            password_hash_lanes,
+            password_hash_memory_kib,
+            password_reset_timeout_hours,
+            token_secret,
+            require_email_confirmation,
+            email_confirmation_timeout_hours,
+            session_idle_timeout_minutes,
+            session_absolute_timeout_days,
+            activity_refresh_seconds,
+            lockout_max_failed_attempts,
+            lockout_duration_minutes,
+            two_factor_issuer,
+            two_factor_required_roles,
         };
 
         let assets = Assets {
@@ -102,6 +422,14 @@ impl Settings {
         let database = Database {
             database_url,
             database_pool,
+            databases,
+            database_min_idle,
+            database_connection_timeout_seconds,
+            database_idle_timeout_seconds,
+            database_max_lifetime_seconds,
+            database_test_on_check_out,
+            database_connect_retries,
+            database_connect_backoff_ms,
         };
 
         let level = parse_log_level(log_level);
@@ -110,7 +438,29 @@ impl Settings {
             terminal_logger,
             file_logger,
             file_logger_dir_path,
+            log_rotate_size_mb,
+            log_rotate_interval: parse_rotate_interval(log_rotate_interval),
+            log_rotate_keep,
+            log_rotate_compress,
             log_level: level,
+            log_format: parse_log_format(log_format),
+            access_log_format: resolve_format(access_log_format),
+        };
+
+        let metrics = Metrics {
+            enabled: metrics_enabled,
+            path: metrics_path,
+            allowed_ips: metrics_allowed_ips,
+        };
+
+        let views = Views {
+            debug_context,
+            template_dir,
+        };
+
+        let rate_limits = RateLimits {
+            store: rate_limit_store,
+            groups: rate_limit_groups,
         };
 
         Settings {
@@ -118,6 +468,9 @@ impl Settings {
             assets,
             database,
             our_logger,
+            metrics,
+            views,
+            rate_limits,
         }
     }
 }
@@ -130,15 +483,93 @@ pub fn unwrap_int(config: &BTreeMap<String, Value>, key: &str) -> i64 {
     config.get(key).unwrap().as_integer().unwrap()
 }
 
+// `{ login = { requests = 5, window_seconds = 60 }, ... }`, incomplete groups are ignored
+fn parse_rate_limits(config: &BTreeMap<String, Value>) -> BTreeMap<String, RateLimitRule> {
+    config
+        .iter()
+        .filter_map(|(group, rule)| {
+            let requests = rule.get(\"requests\").and_then(Value::as_integer);
+            let window_seconds = rule.get(\"window_seconds\").and_then(Value::as_integer);
+            match (requests, window_seconds) {
+                (Some(requests), Some(window_seconds)) => Some((
+                    group.clone(),
+                    RateLimitRule {
+                        requests: requests as u32,
+                        window_seconds: window_seconds as u32,
+                    },
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// `{ replica = { database_url = \"...\", database_pool = 8 }, ... }`, the pool size and the idle
+// connections default to the primary ones and pools without an url are ignored. r2d2 refuses
+// more idle connections than the size of the pool, so `database_min_idle` is capped to it.
+fn parse_databases(
+    config: &BTreeMap<String, Value>,
+    default_pool: u32,
+    default_min_idle: Option<u32>,
+) -> BTreeMap<String, NamedDatabase> {
+    config
+        .iter()
+        .filter_map(|(name, database)| {
+            let database_url = database.get(\"database_url\").and_then(Value::as_str)?;
+            let database_pool = database
+                .get(\"database_pool\")
+                .and_then(Value::as_integer)
+                .map_or(default_pool, |pool| pool as u32);
+            let database_min_idle = database
+                .get(\"database_min_idle\")
+                .and_then(Value::as_integer)
+                .map(|min_idle| min_idle as u32)
+                .or(default_min_idle)
+                .map(|min_idle| min_idle.min(database_pool));
+            Some((
+                name.clone(),
+                NamedDatabase {
+                    database_url: database_url.to_string(),
+                    database_pool,
+                    database_min_idle,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn optional_seconds(seconds: u64) -> Option<time::Duration> {
+    if seconds == 0 {
+        None
+    } else {
+        Some(time::Duration::from_secs(seconds))
+    }
+}
+
 // https://docs.rs/slog/2.1.1/slog/enum.Level.html
 fn parse_log_level(log_level: &str) -> Severity {
-    match log_level {
-        \"Critical\" => Severity::Critical,
-        \"Error\" => Severity::Error,
-        \"Warning\" => Severity::Warning,
-        \"Info\" => Severity::Info,
-        \"Trace\" => Severity::Trace,
+    match log_level.to_lowercase().as_str() {
+        \"critical\" => Severity::Critical,
+        \"error\" => Severity::Error,
+        \"warning\" => Severity::Warning,
+        \"info\" => Severity::Info,
+        \"trace\" => Severity::Trace,
         _ => Severity::Debug,
     }
 }
+
+fn parse_rotate_interval(log_rotate_interval: &str) -> RotateInterval {
+    match log_rotate_interval.to_lowercase().as_str() {
+        \"hourly\" => RotateInterval::Hourly,
+        \"daily\" => RotateInterval::Daily,
+        _ => RotateInterval::Never,
+    }
+}
+
+fn parse_log_format(log_format: &str) -> LogFormat {
+    match log_format.to_lowercase().as_str() {
+        \"json\" => LogFormat::Json,
+        _ => LogFormat::Text,
+    }
+}
 ";
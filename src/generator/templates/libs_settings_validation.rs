@@ -0,0 +1,281 @@
+pub static TEXT: &'static str = "use std::env;
+use std::fs;
+use std::net::IpAddr;
+
+use rocket::config::{Config, Environment, Value};
+
+/// Expected type, and range or values, of a setting. `OneOf` ignores the case,
+/// as do the parsers of `libs/settings.rs`.
+enum Kind {
+    Bool,
+    Int(i64, i64),
+    Str,
+    OneOf(&'static [&'static str]),
+    StrList,
+    Table,
+}
+
+/// Every key read by `Settings::new_from_config`, the `Template` fairing and the mailer.
+const KEYS: &'static [(&'static str, Kind)] = &[
+    (\"access_token_timeout_days\", Kind::Int(1, 3650)),
+    (\"password_salt\", Kind::Str),
+    (\"password_hash_passes\", Kind::Int(1, 100)),
+    (\"password_hash_lanes\", Kind::Int(1, 64)),
+    (\"password_hash_memory_kib\", Kind::Int(8, 4_194_304)),
+    (\"password_reset_timeout_hours\", Kind::Int(1, 168)),
+    (\"token_secret\", Kind::Str),
+    (\"require_email_confirmation\", Kind::Bool),
+    (\"email_confirmation_timeout_hours\", Kind::Int(1, 8760)),
+    (\"session_idle_timeout_minutes\", Kind::Int(1, 525_600)),
+    (\"session_absolute_timeout_days\", Kind::Int(1, 3650)),
+    (\"activity_refresh_seconds\", Kind::Int(0, 86_400)),
+    (\"lockout_max_failed_attempts\", Kind::Int(1, 1000)),
+    (\"lockout_duration_minutes\", Kind::Int(1, 525_600)),
+    (\"two_factor_issuer\", Kind::Str),
+    (\"two_factor_required_roles\", Kind::StrList),
+    (\"rate_limit_store\", Kind::OneOf(&[\"memory\", \"database\"])),
+    (\"rate_limits\", Kind::Table),
+    (\"assets_dir\", Kind::Str),
+    (\"assets_host\", Kind::Str),
+    (\"serve_assets\", Kind::Bool),
+    (\"database_url\", Kind::Str),
+    (\"database_pool\", Kind::Int(1, 1000)),
+    (\"databases\", Kind::Table),
+    (\"database_min_idle\", Kind::Int(0, 1000)),
+    (\"database_connection_timeout_seconds\", Kind::Int(1, 3600)),
+    (\"database_idle_timeout_seconds\", Kind::Int(0, 86_400)),
+    (\"database_max_lifetime_seconds\", Kind::Int(0, 86_400)),
+    (\"database_test_on_check_out\", Kind::Bool),
+    (\"database_connect_retries\", Kind::Int(0, 100)),
+    (\"database_connect_backoff_ms\", Kind::Int(0, 60_000)),
+    (\"terminal_logger\", Kind::Bool),
+    (\"file_logger\", Kind::Bool),
+    (\"file_logger_dir_path\", Kind::Str),
+    (\"log_rotate_size_mb\", Kind::Int(0, 1_048_576)),
+    (\"log_rotate_interval\", Kind::OneOf(&[\"never\", \"hourly\", \"daily\"])),
+    (\"log_rotate_keep\", Kind::Int(0, 10_000)),
+    (\"log_rotate_compress\", Kind::Bool),
+    (
+        \"log_level\",
+        Kind::OneOf(&[\"critical\", \"error\", \"warning\", \"info\", \"debug\", \"trace\"]),
+    ),
+    (\"log_format\", Kind::OneOf(&[\"text\", \"json\"])),
+    (\"access_log_format\", Kind::Str),
+    (\"metrics_enabled\", Kind::Bool),
+    (\"metrics_path\", Kind::Str),
+    (\"metrics_allowed_ips\", Kind::StrList),
+    (\"debug_context\", Kind::Bool),
+    (\"template_dir\", Kind::Str),
+    (\"mailer\", Kind::Table),
+    // Rocket's own settings, only found here when set by `HANGAR_<KEY>` or `<key>_file`
+    (\"address\", Kind::Str),
+    (\"port\", Kind::Int(0, 65_535)),
+    (\"workers\", Kind::Int(1, 65_535)),
+    (\"keep_alive\", Kind::Int(0, 86_400)),
+    (\"secret_key\", Kind::Str),
+];
+
+/// Secrets which must be replaced outside of development: the defaults of the settings
+/// and the values of the generated `[development]` section.
+const DEVELOPMENT_SECRETS: &'static [(&'static str, &'static str)] = &[
+    (\"password_salt\", \"somerandomsalt\"),
+    (\"password_salt\", \"zT0X/fdPU62zSJy3+vvnZg==\"),
+    (\"token_secret\", \"somerandomsecret\"),
+    (\"token_secret\", \"8Yr0UBXjQbTOxfahcVyGcGmc7oVW5Kx4+qxGhwMqpOI=\"),
+    (\"secret_key\", \"Jd1wnIKl0nRIV7TUJWNHcbn3HqtcaBe3uiVTTfIcGDg=\"),
+];
+
+/// Check the settings of the active environment, collecting every error.
+///
+/// Missing keys keep their defaults, except for the secrets outside of development.
+pub fn validate(config: &Config) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    let mut keys: Vec<&String> = config.extras.keys().collect();
+    keys.sort();
+    for key in keys {
+        let value = &config.extras[key];
+        match KEYS.iter().find(|&&(name, _)| name == key.as_str()) {
+            Some(&(_, ref kind)) => check_kind(key, value, kind, &mut errors),
+            None => errors.push(format!(\"`{}` is not a known setting\", key)),
+        }
+    }
+
+    if let Ok(rate_limits) = config.get_table(\"rate_limits\") {
+        for (group, rule) in rate_limits {
+            for field in &[\"requests\", \"window_seconds\"] {
+                match rule.get(*field).and_then(Value::as_integer) {
+                    Some(count) if count >= 1 => {}
+                    _ => errors.push(format!(
+                        \"`rate_limits.{}.{}` must be an integer of at least 1\",
+                        group, field
+                    )),
+                }
+            }
+        }
+    }
+    if let Ok(ips) = config.get_slice(\"metrics_allowed_ips\") {
+        for ip in ips.iter().filter_map(Value::as_str) {
+            if ip.parse::<IpAddr>().is_err() {
+                errors.push(format!(\"`metrics_allowed_ips` contains `{}`, which is not an IP address\", ip));
+            }
+        }
+    }
+    if let Ok(path) = config.get_str(\"metrics_path\") {
+        if !path.starts_with('/') {
+            errors.push(format!(\"`metrics_path` must start with `/`, found `{}`\", path));
+        }
+    }
+    if let Ok(databases) = config.get_table(\"databases\") {
+        for (name, database) in databases {
+            match database.get(\"database_url\").and_then(Value::as_str) {
+                Some(url) if !url.is_empty() => {}
+                _ => errors.push(format!(\"`databases.{}.database_url` must be set\", name)),
+            }
+            if let Some(pool) = database.get(\"database_pool\") {
+                match pool.as_integer() {
+                    Some(pool) if pool >= 1 && pool <= 1000 => {}
+                    _ => errors.push(format!(
+                        \"`databases.{}.database_pool` must be an integer between 1 and 1000\",
+                        name
+                    )),
+                }
+            }
+            if let Some(min_idle) = database.get(\"database_min_idle\") {
+                let pool = database
+                    .get(\"database_pool\")
+                    .and_then(Value::as_integer)
+                    .or_else(|| config.get_int(\"database_pool\").ok());
+                match (min_idle.as_integer(), pool) {
+                    (Some(min_idle), Some(pool)) if min_idle > pool => errors.push(format!(
+                        \"`databases.{}.database_min_idle` must not be above its `database_pool` ({}), found {}\",
+                        name, pool, min_idle
+                    )),
+                    (Some(min_idle), _) if min_idle >= 0 && min_idle <= 1000 => {}
+                    _ => errors.push(format!(
+                        \"`databases.{}.database_min_idle` must be an integer between 0 and 1000\",
+                        name
+                    )),
+                }
+            }
+        }
+    }
+    if let (Ok(min_idle), Ok(pool)) = (
+        config.get_int(\"database_min_idle\"),
+        config.get_int(\"database_pool\"),
+    ) {
+        if min_idle > pool {
+            errors.push(format!(
+                \"`database_min_idle` must not be above `database_pool` ({}), found {}\",
+                pool, min_idle
+            ));
+        }
+    }
+    let has_primary = config
+        .get_table(\"databases\")
+        .map_or(false, |databases| databases.contains_key(\"primary\"));
+    if !has_primary && config.get_str(\"database_url\").map_or(true, str::is_empty) {
+        errors.push(String::from(\"`database_url` must be set\"));
+    }
+
+    if config.environment != Environment::Development {
+        // `secret_key` encrypts the private cookies of the sessions and the CSRF tokens
+        let secrets = [
+            (\"password_salt\", config.get_str(\"password_salt\").ok().map(String::from)),
+            (\"token_secret\", config.get_str(\"token_secret\").ok().map(String::from)),
+            (\"secret_key\", rocket_secret_key(config)),
+        ];
+        for &(key, ref value) in &secrets {
+            let value = value.as_ref().map_or(\"\", String::as_str);
+            let is_default = value.is_empty()
+                || DEVELOPMENT_SECRETS
+                    .iter()
+                    .any(|&(name, secret)| name == key && secret == value);
+            if is_default {
+                errors.push(format!(
+                    \"`{}` must be set to a secret of your own in {}\",
+                    key, config.environment
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// Rocket keeps its `secret_key` to itself, so unless `HANGAR_SECRET_KEY` or `secret_key_file`
+// override it, it is looked up the way Rocket does:
+// `ROCKET_SECRET_KEY`, then `[global]`, then the section of the environment
+fn rocket_secret_key(config: &Config) -> Option<String> {
+    if let Ok(secret_key) = config.get_str(\"secret_key\") {
+        return Some(secret_key.to_string());
+    }
+    if let Ok(secret_key) = env::var(\"ROCKET_SECRET_KEY\") {
+        return Some(secret_key);
+    }
+    let path = config.root()?.join(\"Rocket.toml\");
+    let document = fs::read_to_string(path).ok()?.parse::<Value>().ok()?;
+    let sections = [String::from(\"global\"), config.environment.to_string()];
+    sections
+        .iter()
+        .filter_map(|section| {
+            let secret_key = document.get(section.as_str())?.get(\"secret_key\")?;
+            secret_key.as_str().map(String::from)
+        })
+        .next()
+}
+
+/// One error per line, for the launch failure.
+pub fn report(environment: Environment, errors: &[String]) -> String {
+    let mut report = format!(\"Invalid settings for {} in Rocket.toml:\", environment);
+    for error in errors {
+        report.push_str(\"\\n  - \");
+        report.push_str(error);
+    }
+    report
+}
+
+fn check_kind(key: &str, value: &Value, kind: &Kind, errors: &mut Vec<String>) {
+    let error = match *kind {
+        Kind::Bool if !value.is_bool() => Some(wrong_type(key, \"a boolean\", value)),
+        Kind::Int(min, max) => match value.as_integer() {
+            Some(int) if int < min || int > max => Some(format!(
+                \"`{}` must be between {} and {}, found {}\",
+                key, min, max, int
+            )),
+            Some(_) => None,
+            None => Some(wrong_type(key, \"an integer\", value)),
+        },
+        Kind::Str if !value.is_str() => Some(wrong_type(key, \"a string\", value)),
+        Kind::OneOf(allowed) => match value.as_str() {
+            Some(string) if !allowed.iter().any(|name| name.eq_ignore_ascii_case(string)) => {
+                Some(format!(
+                    \"`{}` must be one of {}, found `{}`\",
+                    key,
+                    allowed.join(\", \"),
+                    string
+                ))
+            }
+            Some(_) => None,
+            None => Some(wrong_type(key, \"a string\", value)),
+        },
+        Kind::StrList => match value.as_array() {
+            Some(items) if items.iter().all(Value::is_str) => None,
+            _ => Some(wrong_type(key, \"an array of strings\", value)),
+        },
+        Kind::Table if !value.is_table() => Some(wrong_type(key, \"a table\", value)),
+        _ => None,
+    };
+    if let Some(error) = error {
+        errors.push(error);
+    }
+}
+
+fn wrong_type(key: &str, expected: &str, value: &Value) -> String {
+    format!(\"`{}` must be {}, found {}\", key, expected, value.type_str())
+}
+";
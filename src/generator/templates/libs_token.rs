@@ -0,0 +1,39 @@
+pub static TEXT: &'static str = "use rand::{OsRng, Rng};
+use ring::constant_time::verify_slices_are_equal;
+use ring::digest::{digest, SHA256};
+use ring::hmac;
+
+const TOKEN_BYTES: usize = 24;
+
+/// Random token to be sent to the user, lowercase hex so it survives the lowercased url.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    let mut rand_gen = OsRng::new().expect(\"Couldn't make OsRng!\");
+    rand_gen.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// SHA-256 of `token`, only this is stored in the database.
+pub fn hash_token(token: &str) -> String {
+    to_hex(digest(&SHA256, token.as_bytes()).as_ref())
+}
+
+/// HMAC-SHA256 of `message` with `secret`, for links which are verified without being stored.
+pub fn sign(secret: &str, message: &str) -> String {
+    let key = hmac::SigningKey::new(&SHA256, secret.as_bytes());
+    to_hex(hmac::sign(&key, message.as_bytes()).as_ref())
+}
+
+/// Check `signature` made by `sign`, in constant time.
+pub fn verify_signature(secret: &str, message: &str, signature: &str) -> bool {
+    verify_slices_are_equal(sign(secret, message).as_bytes(), signature.as_bytes()).is_ok()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!(\"{:02x}\", byte))
+        .collect::<Vec<String>>()
+        .join(\"\")
+}
+";
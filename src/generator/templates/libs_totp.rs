@@ -0,0 +1,135 @@
+pub static TEXT: &'static str = "use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::{OsRng, Rng};
+use ring::constant_time::verify_slices_are_equal;
+use ring::digest::SHA1;
+use ring::hmac;
+
+/// Digits of a code, as expected by the authenticator apps.
+pub const TOTP_DIGITS: u32 = 6;
+/// Seconds during which a code is valid.
+pub const TOTP_PERIOD: i64 = 30;
+/// Steps accepted before and after the current one, for clock drift.
+const TOTP_SKEW: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const BASE32_ALPHABET: &[u8] = b\"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567\";
+
+/// Random base32 secret to be shared with the authenticator app.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    let mut rand_gen = OsRng::new().expect(\"Couldn't make OsRng!\");
+    rand_gen.fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+/// RFC 6238 code of `secret` for the time step `counter`.
+pub fn code_at(secret: &[u8], counter: u64) -> String {
+    let key = hmac::SigningKey::new(&SHA1, secret);
+    let mut message = [0u8; 8];
+    for (i, byte) in message.iter_mut().enumerate() {
+        *byte = (counter >> (8 * (7 - i))) as u8;
+    }
+    let signature = hmac::sign(&key, &message);
+    let hash = signature.as_ref();
+
+    // Dynamic truncation of RFC 4226
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = (u32::from(hash[offset]) & 0x7f) << 24 | u32::from(hash[offset + 1]) << 16
+        | u32::from(hash[offset + 2]) << 8 | u32::from(hash[offset + 3]);
+    format!(
+        \"{:0width$}\",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// Time step of the valid `code` for the base32 `secret` at the unix time `now`.
+///
+/// Steps up to `last_counter` are refused, so a code can't be replayed.
+pub fn verify_code(secret: &str, code: &str, now: i64, last_counter: Option<i64>) -> Option<i64> {
+    let secret = base32_decode(secret)?;
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize {
+        return None;
+    }
+
+    let current = now / TOTP_PERIOD;
+    (current - TOTP_SKEW..current + TOTP_SKEW + 1)
+        .filter(|&counter| counter >= 0 && last_counter.map_or(true, |last| counter > last))
+        .find(|&counter| {
+            let expected = code_at(&secret, counter as u64);
+            verify_slices_are_equal(expected.as_bytes(), code.as_bytes()).is_ok()
+        })
+}
+
+/// `otpauth://` URI of the QR code scanned by the authenticator apps.
+pub fn provisioning_uri(secret: &str, account: &str, issuer: &str) -> String {
+    format!(
+        \"otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}\",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD
+    )
+}
+
+/// SVG QR code of `uri`, to be rendered unescaped in the views.
+pub fn qr_code_svg(uri: &str) -> Option<String> {
+    QrCode::new(uri.as_bytes()).ok().map(|code| {
+        code.render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .build()
+    })
+}
+
+/// RFC 4648 base32, without padding.
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// Decode base32, ignoring case, spaces and padding.
+pub fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for character in encoded.chars().filter(|c| *c != ' ' && *c != '=') {
+        let upper = character.to_ascii_uppercase() as u8;
+        let value = BASE32_ALPHABET.iter().position(|&c| c == upper)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'...b'Z' | b'a'...b'z' | b'0'...b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!(\"%{:02X}\", byte),
+        })
+        .collect()
+}
+";
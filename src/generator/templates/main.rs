@@ -1,7 +1,16 @@
 pub static TEXT: &'static str = "extern crate hangar;
 
+use std::process;
+
 fn main() {
-    let rocket = hangar::rocket_factory().unwrap();
-    rocket.launch();
+    match hangar::rocket_factory() {
+        Ok(rocket) => {
+            rocket.launch();
+        }
+        Err(error) => {
+            eprintln!(\"{}\", error);
+            process::exit(1);
+        }
+    }
 }
 ";
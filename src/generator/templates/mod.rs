@@ -4,35 +4,80 @@ pub mod blank;
 pub mod favicon_svg;
 pub mod gitignore;
 
+pub mod apis_health;
+pub mod apis_mod;
+pub mod apis_tokens;
 pub mod controller_assets;
+pub mod controller_confirmations;
+pub mod controller_metrics;
 pub mod controller_mod;
 pub mod controller_pages;
+pub mod controller_password_resets;
+pub mod controller_sessions;
+pub mod controller_two_factor;
+pub mod controller_unlocks;
 pub mod controller_users;
 pub mod db_sqlite;
 pub mod db_mysql;
 pub mod db_postgresql;
+pub mod deployment_compose;
+pub mod deployment_dockerfile;
+pub mod deployment_systemd;
+pub mod guards_authorization;
 pub mod guards_mod;
+pub mod guards_password_reset;
 pub mod guards_sanitized_str;
+pub mod guards_session;
+pub mod guards_two_factor;
 pub mod guards_user;
 pub mod libs_conn;
 pub mod libs_const;
+pub mod libs_csrf;
 pub mod libs_db;
 pub mod libs_email;
+pub mod libs_email_disabled;
+pub mod libs_env_overrides;
 pub mod libs_init;
 pub mod libs_log_format;
+pub mod libs_log_rotation;
 pub mod libs_logger;
+pub mod libs_mailer_config;
+pub mod libs_metrics;
 pub mod libs_mod;
+pub mod libs_password;
+pub mod libs_rate_limit;
+pub mod libs_request_log;
 pub mod libs_routes;
 pub mod libs_settings;
+pub mod libs_settings_validation;
+pub mod libs_token;
+pub mod libs_totp;
 pub mod model_mod;
+pub mod model_password_reset;
+pub mod model_recovery_code;
+pub mod model_role;
+pub mod model_session;
 pub mod model_user;
 pub mod model_user_postgres;
+pub mod policies_mod;
+pub mod policies_user;
+pub mod view_models_confirmations;
 pub mod view_models_mod;
+pub mod view_models_password_resets;
+pub mod view_models_sessions;
+pub mod view_models_two_factor;
 pub mod view_models_users;
 pub mod html_404;
 pub mod html_base_layout;
+pub mod html_confirmations_new;
 pub mod html_index;
 pub mod html_macros;
+pub mod html_password_resets_edit;
+pub mod html_password_resets_new;
+pub mod html_sessions_index;
+pub mod html_two_factor_new;
+pub mod html_two_factor_recovery_codes;
+pub mod html_two_factor_verify;
 pub mod html_users_show;
 pub mod html_users_signin;
 pub mod html_users_signup;
@@ -42,6 +87,11 @@ pub mod lib;
 pub mod main;
 
 pub mod test_lib;
+pub mod test_libs_csrf;
+pub mod test_libs_env_overrides;
+pub mod test_libs_mod;
+pub mod test_libs_settings_validation;
+pub mod test_libs_totp;
 
 pub mod dotenv;
 pub mod cargo_toml;
@@ -1,2 +1,6 @@
-pub static TEXT: &'static str = "pub mod user;
+pub static TEXT: &'static str = "pub mod password_reset;
+pub mod recovery_code;
+pub mod role;
+pub mod session;
+pub mod user;
 ";
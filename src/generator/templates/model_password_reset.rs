@@ -0,0 +1,80 @@
+pub static TEXT: &'static str = "use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::prelude::*;
+use diesel::result::Error;
+use schema::password_resets;
+
+use libs::db::ConnPool;
+use libs::token::{generate_token, hash_token};
+use models::user::User;
+
+#[derive(Debug, Queryable, Identifiable)]
+#[table_name = \"password_resets\"]
+pub struct PasswordReset {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = \"password_resets\"]
+pub struct NewPasswordReset {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+}
+
+impl PasswordReset {
+    /// Create a password reset for `user` and return its token, only the token hash is stored.
+    pub fn create_for_user(user: &User, valid_for: Duration, db: &ConnPool) -> Result<String, Error> {
+        let token = generate_token();
+        let new_password_reset = NewPasswordReset {
+            user_id: user.id,
+            token_hash: hash_token(&token),
+            expires_at: (Utc::now() + valid_for).naive_utc(),
+        };
+
+        diesel::insert_into(password_resets::table)
+            .values(&new_password_reset)
+            .execute(&***db)?;
+        Ok(token)
+    }
+
+    /// Unused and unexpired password reset for `token`.
+    pub fn find_valid(token: &str, db: &ConnPool) -> Result<Option<Self>, Error> {
+        password_resets::table
+            .filter(password_resets::token_hash.eq(hash_token(token)))
+            .filter(password_resets::used_at.is_null())
+            .filter(password_resets::expires_at.gt(Utc::now().naive_utc()))
+            .first::<PasswordReset>(&***db)
+            .optional()
+    }
+
+    /// Mark this password reset as used, `false` when it was used or expired in the meantime.
+    pub fn claim(&self, db: &ConnPool) -> Result<bool, Error> {
+        let now = Utc::now().naive_utc();
+        let claimed = diesel::update(
+            password_resets::table
+                .filter(password_resets::id.eq(self.id))
+                .filter(password_resets::used_at.is_null())
+                .filter(password_resets::expires_at.gt(now)),
+        ).set(password_resets::used_at.eq(Some(now)))
+            .execute(&***db)?;
+        Ok(claimed == 1)
+    }
+
+    /// Use up this and every other pending password reset of the same user.
+    pub fn use_all(&self, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(
+            password_resets::table
+                .filter(password_resets::user_id.eq(self.user_id))
+                .filter(password_resets::used_at.is_null()),
+        ).set(password_resets::used_at.eq(Some(Utc::now().naive_utc())))
+            .execute(&***db)?;
+        Ok(())
+    }
+}
+";
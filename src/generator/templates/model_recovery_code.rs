@@ -0,0 +1,92 @@
+pub static TEXT: &'static str = "use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::prelude::*;
+use diesel::result::Error;
+use schema::recovery_codes;
+
+use libs::db::ConnPool;
+use libs::settings::ApplicationSecurity;
+use libs::token::{generate_token, sign};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Debug, Queryable, Identifiable)]
+#[table_name = \"recovery_codes\"]
+pub struct RecoveryCode {
+    pub id: i32,
+    pub user_id: i32,
+    pub code_hash: String,
+    pub used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = \"recovery_codes\"]
+pub struct NewRecoveryCode {
+    pub user_id: i32,
+    pub code_hash: String,
+}
+
+impl RecoveryCode {
+    /// Replace the recovery codes of `user_id` and return the new ones, only their HMAC is stored.
+    pub fn generate_for_user(
+        user_id: i32,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<Vec<String>, Error> {
+        RecoveryCode::delete_for_user(user_id, db)?;
+
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let token = generate_token();
+                format!(\"{}-{}\", &token[..5], &token[5..10])
+            })
+            .collect();
+        let new_codes: Vec<NewRecoveryCode> = codes
+            .iter()
+            .map(|code| NewRecoveryCode {
+                user_id,
+                code_hash: code_hash(code, security),
+            })
+            .collect();
+
+        diesel::insert_into(recovery_codes::table)
+            .values(&new_codes)
+            .execute(&***db)?;
+        Ok(codes)
+    }
+
+    /// Use up the unused recovery `code` of `user_id`, returns whether it was valid.
+    pub fn use_code(
+        user_id: i32,
+        code: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<bool, Error> {
+        let now = Utc::now().naive_utc();
+        let used = diesel::update(
+            recovery_codes::table
+                .filter(recovery_codes::user_id.eq(user_id))
+                .filter(recovery_codes::code_hash.eq(code_hash(code, security)))
+                .filter(recovery_codes::used_at.is_null()),
+        ).set(recovery_codes::used_at.eq(Some(now)))
+            .execute(&***db)?;
+        Ok(used > 0)
+    }
+
+    pub fn delete_for_user(user_id: i32, db: &ConnPool) -> Result<(), Error> {
+        diesel::delete(recovery_codes::table.filter(recovery_codes::user_id.eq(user_id)))
+            .execute(&***db)?;
+        Ok(())
+    }
+}
+
+// Codes are compared without their dash and case, as typed by the user
+fn code_hash(code: &str, security: &ApplicationSecurity) -> String {
+    let normalized: String = code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    sign(&security.token_secret, &format!(\"recovery_code:{}\", normalized))
+}
+";
@@ -0,0 +1,81 @@
+pub static TEXT: &'static str = "use diesel;
+use diesel::prelude::*;
+use diesel::result::Error;
+use schema::{permissions, role_permissions, roles, user_roles};
+
+use libs::db::ConnPool;
+
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[table_name = \"roles\"]
+pub struct Role {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(Insertable)]
+#[table_name = \"user_roles\"]
+pub struct NewUserRole {
+    pub user_id: i32,
+    pub role_id: i32,
+}
+
+impl Role {
+    pub fn find_by_name(name: &str, db: &ConnPool) -> Result<Option<Self>, Error> {
+        roles::table
+            .filter(roles::name.eq(name))
+            .first::<Role>(&***db)
+            .optional()
+    }
+
+    /// Names of the roles granted to `user_id`.
+    pub fn names_for_user(user_id: i32, db: &ConnPool) -> Result<Vec<String>, Error> {
+        let role_ids = user_roles::table
+            .select(user_roles::role_id)
+            .filter(user_roles::user_id.eq(user_id));
+        roles::table
+            .select(roles::name)
+            .filter(roles::id.eq_any(role_ids))
+            .load::<String>(&***db)
+    }
+
+    /// Names of the permissions of every role granted to `user_id`.
+    pub fn permissions_for_user(user_id: i32, db: &ConnPool) -> Result<Vec<String>, Error> {
+        let role_ids = user_roles::table
+            .select(user_roles::role_id)
+            .filter(user_roles::user_id.eq(user_id));
+        let permission_ids = role_permissions::table
+            .select(role_permissions::permission_id)
+            .filter(role_permissions::role_id.eq_any(role_ids));
+        permissions::table
+            .select(permissions::name)
+            .filter(permissions::id.eq_any(permission_ids))
+            .load::<String>(&***db)
+    }
+
+    pub fn grant_to_user(&self, user_id: i32, db: &ConnPool) -> Result<(), Error> {
+        let granted = user_roles::table
+            .filter(user_roles::user_id.eq(user_id))
+            .filter(user_roles::role_id.eq(self.id))
+            .count()
+            .get_result::<i64>(&***db)?;
+        if granted == 0 {
+            diesel::insert_into(user_roles::table)
+                .values(&NewUserRole {
+                    user_id,
+                    role_id: self.id,
+                })
+                .execute(&***db)?;
+        }
+        Ok(())
+    }
+
+    pub fn revoke_from_user(&self, user_id: i32, db: &ConnPool) -> Result<(), Error> {
+        diesel::delete(
+            user_roles::table
+                .filter(user_roles::user_id.eq(user_id))
+                .filter(user_roles::role_id.eq(self.id)),
+        ).execute(&***db)?;
+        Ok(())
+    }
+}
+";
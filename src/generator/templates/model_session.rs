@@ -0,0 +1,141 @@
+pub static TEXT: &'static str = "use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::prelude::*;
+use diesel::result::Error;
+use schema::sessions;
+
+use guards::session::ClientInfo;
+use libs::db::ConnPool;
+use libs::settings::ApplicationSecurity;
+use libs::token::{generate_token, hash_token};
+
+#[derive(Debug, Serialize, Queryable, Identifiable)]
+#[table_name = \"sessions\"]
+pub struct Session {
+    pub id: i32,
+    pub user_id: i32,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = \"sessions\"]
+pub struct NewSession {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+}
+
+impl Session {
+    /// Start a session for `user_id` and return its token, only the token hash is stored.
+    pub fn create_for_user(user_id: i32, client: &ClientInfo, db: &ConnPool) -> Result<String, Error> {
+        let token = generate_token();
+        let now = Utc::now().naive_utc();
+        let new_session = NewSession {
+            user_id,
+            token_hash: hash_token(&token),
+            user_agent: client.user_agent.clone(),
+            ip_address: client.ip_address.clone(),
+            created_at: now,
+            last_seen_at: now,
+        };
+
+        diesel::insert_into(sessions::table)
+            .values(&new_session)
+            .execute(&***db)?;
+        Ok(token)
+    }
+
+    /// Unrevoked session for `token`, within the idle and absolute timeouts.
+    pub fn find_active(
+        token: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<Option<Self>, Error> {
+        let now = Utc::now().naive_utc();
+        sessions::table
+            .filter(sessions::token_hash.eq(hash_token(token)))
+            .filter(sessions::revoked_at.is_null())
+            .filter(sessions::last_seen_at.gt(now - security.session_idle_duration()))
+            .filter(sessions::created_at.gt(now - security.session_absolute_duration()))
+            .first::<Session>(&***db)
+            .optional()
+    }
+
+    /// Active sessions of `user_id`, most recently seen first.
+    pub fn active_for_user(
+        user_id: i32,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<Vec<Self>, Error> {
+        let now = Utc::now().naive_utc();
+        sessions::table
+            .filter(sessions::user_id.eq(user_id))
+            .filter(sessions::revoked_at.is_null())
+            .filter(sessions::last_seen_at.gt(now - security.session_idle_duration()))
+            .filter(sessions::created_at.gt(now - security.session_absolute_duration()))
+            .order(sessions::last_seen_at.desc())
+            .load::<Session>(&***db)
+    }
+
+    /// Keep the session from idling out.
+    pub fn touch(&self, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(sessions::table.find(self.id))
+            .set(sessions::last_seen_at.eq(Utc::now().naive_utc()))
+            .execute(&***db)?;
+        Ok(())
+    }
+
+    pub fn revoke_token(token: &str, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(
+            sessions::table
+                .filter(sessions::token_hash.eq(hash_token(token)))
+                .filter(sessions::revoked_at.is_null()),
+        ).set(sessions::revoked_at.eq(Some(Utc::now().naive_utc())))
+            .execute(&***db)?;
+        Ok(())
+    }
+
+    /// Revoke the session `id`, only if it belongs to `user_id`.
+    pub fn revoke_for_user(user_id: i32, id: i32, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(
+            sessions::table
+                .filter(sessions::id.eq(id))
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::revoked_at.is_null()),
+        ).set(sessions::revoked_at.eq(Some(Utc::now().naive_utc())))
+            .execute(&***db)?;
+        Ok(())
+    }
+
+    /// Revoke every session of `user_id` but `current_id`.
+    pub fn revoke_others(user_id: i32, current_id: i32, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(
+            sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::id.ne(current_id))
+                .filter(sessions::revoked_at.is_null()),
+        ).set(sessions::revoked_at.eq(Some(Utc::now().naive_utc())))
+            .execute(&***db)?;
+        Ok(())
+    }
+
+    pub fn revoke_all_for_user(user_id: i32, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(
+            sessions::table
+                .filter(sessions::user_id.eq(user_id))
+                .filter(sessions::revoked_at.is_null()),
+        ).set(sessions::revoked_at.eq(Some(Utc::now().naive_utc())))
+            .execute(&***db)?;
+        Ok(())
+    }
+}
+";
@@ -1,6 +1,5 @@
 pub static TEXT: &'static str = "use std::fmt;
 
-use argon2rs::argon2i_simple;
 use chrono::{Duration, NaiveDateTime, Utc};
 use diesel;
 use diesel::prelude::*;
@@ -10,24 +9,41 @@ use rand::{OsRng, Rng};
 
 use guards::user::Registration;
 use libs::db::ConnPool;
+use libs::password::{hash_password, needs_rehash, verify_legacy_password, verify_password};
+use libs::settings::ApplicationSecurity;
+use libs::token::{sign, verify_signature};
+use libs::totp::{generate_secret, verify_code};
+use models::recovery_code::RecoveryCode;
+use models::role::Role;
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Identifiable, AsChangeset)]
 #[table_name = \"users\"]
 pub struct User {
     pub id: i32,
     pub email: String,
-    pub encrypted_password: Vec<u8>,
+    #[serde(skip_serializing)]
+    pub encrypted_password: Option<Vec<u8>>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub access_token: Option<String>,
     pub last_access: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    pub confirmed_at: Option<NaiveDateTime>,
+    pub failed_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    #[serde(skip_serializing)]
+    pub totp_last_counter: Option<i64>,
 }
 
 #[derive(Insertable, Serialize, Deserialize)]
 #[table_name = \"users\"]
 pub struct NewUser {
     pub email: String,
-    pub encrypted_password: Vec<u8>,
+    pub password_hash: String,
 }
 
 impl fmt::Display for User {
@@ -49,9 +65,292 @@ impl fmt::Display for NewUser {
 
 impl User {
     // https://tools.ietf.org/html/draft-josefsson-argon2-00#section-1
-    pub fn verify_password(&self, password: &str, salt: &str) -> bool {
-        let hash = argon2i_simple(password, salt).to_vec();
-        self.encrypted_password == hash
+    pub fn verify_password(&self, password: &str, security: &ApplicationSecurity) -> bool {
+        match (&self.password_hash, &self.encrypted_password) {
+            (&Some(ref password_hash), _) => verify_password(password, password_hash),
+            (&None, &Some(ref encrypted_password)) => {
+                verify_legacy_password(password, &security.password_salt, encrypted_password)
+            }
+            (&None, &None) => false,
+        }
+    }
+
+    /// Rehash a verified `password` when it was hashed with the global salt or older parameters.
+    pub fn rehash_password_if_needed(
+        &mut self,
+        password: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<(), Error> {
+        let outdated = match self.password_hash {
+            Some(ref password_hash) => needs_rehash(password_hash, security),
+            None => true,
+        };
+        if !outdated {
+            return Ok(());
+        }
+        self.update_password(password, security, db)
+    }
+
+    /// Store a new hash of `password`, dropping the legacy hash.
+    pub fn update_password(
+        &mut self,
+        password: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<(), Error> {
+        let password_hash = hash_password(password, security);
+        diesel::update(users::table.find(self.id))
+            .set((
+                users::password_hash.eq(&password_hash),
+                users::encrypted_password.eq(None::<Vec<u8>>),
+            ))
+            .execute(&***db)?;
+        self.password_hash = Some(password_hash);
+        self.encrypted_password = None;
+        Ok(())
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    /// Signed `<id>-<expires_at>-<signature>` token for the email confirmation link.
+    /// Changing the email invalidates the token.
+    pub fn confirmation_token(&self, security: &ApplicationSecurity) -> String {
+        let expires_at = (Utc::now() + security.email_confirmation_duration()).timestamp();
+        let signature = sign(
+            &security.token_secret,
+            &self.confirmation_message(expires_at),
+        );
+        format!(\"{}-{}-{}\", self.id, expires_at, signature)
+    }
+
+    /// The user of an unexpired confirmation `token` with a valid signature.
+    pub fn from_confirmation_token(
+        token: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<Option<Self>, Error> {
+        let (id, expires_at, signature) = match parse_signed_token(token) {
+            Some(parts) => parts,
+            None => return Ok(None),
+        };
+        if expires_at < Utc::now().timestamp() {
+            return Ok(None);
+        }
+
+        let user = users::dsl::users.find(id).first::<User>(&***db).optional()?;
+        Ok(user.and_then(|user| {
+            let message = user.confirmation_message(expires_at);
+            if verify_signature(&security.token_secret, &message, signature) {
+                Some(user)
+            } else {
+                None
+            }
+        }))
+    }
+
+    pub fn confirm(&mut self, db: &ConnPool) -> Result<(), Error> {
+        if self.is_confirmed() {
+            return Ok(());
+        }
+        let now = Utc::now().naive_utc();
+        diesel::update(users::table.find(self.id))
+            .set(users::confirmed_at.eq(Some(now)))
+            .execute(&***db)?;
+        self.confirmed_at = Some(now);
+        Ok(())
+    }
+
+    fn confirmation_message(&self, expires_at: i64) -> String {
+        format!(\"confirmation:{}:{}:{}\", self.id, self.email, expires_at)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        match self.locked_until {
+            Some(locked_until) => locked_until > Utc::now().naive_utc(),
+            None => false,
+        }
+    }
+
+    /// Count a failed login, the account is locked for `lockout_duration_minutes`
+    /// after `lockout_max_failed_attempts`. Returns whether this failure locked it.
+    pub fn register_failed_login(
+        &mut self,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<bool, Error> {
+        self.failed_attempts += 1;
+        let locked = self.failed_attempts >= security.lockout_max_failed_attempts as i32;
+        if locked {
+            self.failed_attempts = 0;
+            self.locked_until = Some((Utc::now() + security.lockout_duration()).naive_utc());
+        }
+        diesel::update(users::table.find(self.id))
+            .set((
+                users::failed_attempts.eq(self.failed_attempts),
+                users::locked_until.eq(self.locked_until),
+            ))
+            .execute(&***db)?;
+        Ok(locked)
+    }
+
+    /// Forget failed logins and unlock, after a successful login or an unlock link.
+    pub fn reset_failed_logins(&mut self, db: &ConnPool) -> Result<(), Error> {
+        if self.failed_attempts == 0 && self.locked_until.is_none() {
+            return Ok(());
+        }
+        diesel::update(users::table.find(self.id))
+            .set((
+                users::failed_attempts.eq(0),
+                users::locked_until.eq(None::<NaiveDateTime>),
+            ))
+            .execute(&***db)?;
+        self.failed_attempts = 0;
+        self.locked_until = None;
+        Ok(())
+    }
+
+    /// Signed `<id>-<locked_until>-<signature>` token for the unlock link, valid while locked.
+    pub fn unlock_token(&self, security: &ApplicationSecurity) -> Option<String> {
+        self.locked_until.map(|locked_until| {
+            let locked_until = locked_until.timestamp();
+            let signature = sign(&security.token_secret, &self.unlock_message(locked_until));
+            format!(\"{}-{}-{}\", self.id, locked_until, signature)
+        })
+    }
+
+    /// The locked user of an unlock `token` with a valid signature.
+    pub fn from_unlock_token(
+        token: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<Option<Self>, Error> {
+        let (id, locked_until, signature) = match parse_signed_token(token) {
+            Some(parts) => parts,
+            None => return Ok(None),
+        };
+
+        let user = users::dsl::users.find(id).first::<User>(&***db).optional()?;
+        Ok(user.and_then(|user| {
+            let current_lock = user.locked_until.map(|locked_until| locked_until.timestamp());
+            let message = user.unlock_message(locked_until);
+            if user.is_locked() && current_lock == Some(locked_until)
+                && verify_signature(&security.token_secret, &message, signature)
+            {
+                Some(user)
+            } else {
+                None
+            }
+        }))
+    }
+
+    fn unlock_message(&self, locked_until: i64) -> String {
+        format!(\"unlock:{}:{}:{}\", self.id, self.email, locked_until)
+    }
+
+    pub fn has_two_factor(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+
+    /// Whether signing in needs a second factor, because it is enabled
+    /// or one of the roles is in `two_factor_required_roles`.
+    pub fn requires_two_factor(
+        &self,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<bool, Error> {
+        if self.has_two_factor() {
+            return Ok(true);
+        }
+        if security.two_factor_required_roles.is_empty() {
+            return Ok(false);
+        }
+        let roles = Role::names_for_user(self.id, db)?;
+        Ok(roles
+            .iter()
+            .any(|role| security.two_factor_required_roles.contains(role)))
+    }
+
+    /// TOTP secret being enrolled, kept until the first code is verified
+    /// so reloading the page doesn't invalidate the scanned QR code.
+    pub fn start_two_factor_enrollment(&mut self, db: &ConnPool) -> Result<String, Error> {
+        if let Some(ref totp_secret) = self.totp_secret {
+            return Ok(totp_secret.clone());
+        }
+        let totp_secret = generate_secret();
+        diesel::update(users::table.find(self.id))
+            .set(users::totp_secret.eq(Some(totp_secret.clone())))
+            .execute(&***db)?;
+        self.totp_secret = Some(totp_secret.clone());
+        Ok(totp_secret)
+    }
+
+    /// Enable two-factor authentication when `code` is valid for the enrolled secret.
+    pub fn enable_two_factor(&mut self, code: &str, db: &ConnPool) -> Result<bool, Error> {
+        if self.has_two_factor() || !self.verify_totp_code(code, db)? {
+            return Ok(false);
+        }
+        let now = Utc::now().naive_utc();
+        diesel::update(users::table.find(self.id))
+            .set(users::totp_enabled_at.eq(Some(now)))
+            .execute(&***db)?;
+        self.totp_enabled_at = Some(now);
+        Ok(true)
+    }
+
+    /// Check a second factor, either a TOTP `code` or an unused recovery code.
+    pub fn verify_two_factor_code(
+        &mut self,
+        code: &str,
+        security: &ApplicationSecurity,
+        db: &ConnPool,
+    ) -> Result<bool, Error> {
+        if !self.has_two_factor() {
+            return Ok(false);
+        }
+        if self.verify_totp_code(code, db)? {
+            return Ok(true);
+        }
+        RecoveryCode::use_code(self.id, code, security, db)
+    }
+
+    /// Check a TOTP `code`, each time step is accepted only once.
+    fn verify_totp_code(&mut self, code: &str, db: &ConnPool) -> Result<bool, Error> {
+        let counter = match self.totp_secret {
+            Some(ref totp_secret) => verify_code(
+                totp_secret,
+                code,
+                Utc::now().timestamp(),
+                self.totp_last_counter,
+            ),
+            None => None,
+        };
+        match counter {
+            Some(counter) => {
+                diesel::update(users::table.find(self.id))
+                    .set(users::totp_last_counter.eq(Some(counter)))
+                    .execute(&***db)?;
+                self.totp_last_counter = Some(counter);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn disable_two_factor(&mut self, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(users::table.find(self.id))
+            .set((
+                users::totp_secret.eq(None::<String>),
+                users::totp_enabled_at.eq(None::<NaiveDateTime>),
+                users::totp_last_counter.eq(None::<i64>),
+            ))
+            .execute(&***db)?;
+        self.totp_secret = None;
+        self.totp_enabled_at = None;
+        self.totp_last_counter = None;
+        RecoveryCode::delete_for_user(self.id, db)
     }
 
     pub fn generate_access_token(&mut self, conn: &ConnPool) -> Result<String, Error> {
@@ -59,7 +358,7 @@ impl User {
         let new_access_token = rand_gen.gen_ascii_chars().take(32).collect::<String>();
         self.access_token = Some(new_access_token.clone());
         self.last_access = Some(Utc::now().naive_utc());
-        self.save_changes::<User>(conn)?;
+        self.save_changes::<User>(&***conn)?;
         Ok(new_access_token)
     }
 
@@ -76,40 +375,52 @@ impl User {
         }
     }
 
-    pub fn from_id(id: i32, db: ConnPool) -> Result<Option<Self>, diesel::result::Error> {
+    /// Extend the validity of the access token, which expires after `access_token_timeout_days` unused.
+    pub fn touch_access_token(&mut self, db: &ConnPool) -> Result<(), Error> {
+        self.last_access = Some(Utc::now().naive_utc());
+        diesel::update(users::table.find(self.id))
+            .set(users::last_access.eq(self.last_access))
+            .execute(&***db)?;
+        Ok(())
+    }
+
+    pub fn revoke_access_token(&mut self, db: &ConnPool) -> Result<(), Error> {
+        diesel::update(users::table.find(self.id))
+            .set(users::access_token.eq(None::<String>))
+            .execute(&***db)?;
+        self.access_token = None;
+        Ok(())
+    }
+
+    pub fn from_id(id: i32, db: &ConnPool) -> Result<Option<Self>, diesel::result::Error> {
         users::dsl::users
             .find(id)
-            .first::<User>(&**db)
+            .first::<User>(&***db)
             .optional()
     }
 
     pub fn from_access_token(
         access_token: &str,
-        db: ConnPool,
+        db: &ConnPool,
     ) -> Result<Option<Self>, diesel::result::Error> {
         users::dsl::users
             .filter(users::access_token.eq(access_token))
-            .first::<User>(&**db)
+            .first::<User>(&***db)
             .optional()
     }
 }
 
 impl NewUser {
-    fn make_encrypted_password(password: &str, salt: &str) -> Vec<u8> {
-        argon2i_simple(password, salt).to_vec()
-    }
-
     pub fn new_from_user_data(
         user_data: &Registration,
-        salt: &str,
+        security: &ApplicationSecurity,
         db: &ConnPool,
     ) -> Result<User, diesel::result::Error> {
-        let encrypted_password =
-            NewUser::make_encrypted_password(user_data.password.as_str(), salt);
+        let password_hash = hash_password(user_data.password.as_str(), security);
 
         let new_user = NewUser {
             email: user_data.email.clone().unwrap(),
-            encrypted_password,
+            password_hash,
         };
 
         diesel::insert_into(users::table)
@@ -117,4 +428,16 @@ impl NewUser {
             .get_result::<User>(&***db)
     }
 }
+
+// `<id>-<timestamp>-<signature>` of the signed tokens
+fn parse_signed_token(token: &str) -> Option<(i32, i64, &str)> {
+    let parts: Vec<&str> = token.splitn(3, '-').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    match (parts[0].parse::<i32>(), parts[1].parse::<i64>()) {
+        (Ok(id), Ok(timestamp)) => Some((id, timestamp, parts[2])),
+        _ => None,
+    }
+}
 ";
@@ -0,0 +1,42 @@
+pub static TEXT: &'static str = "use rocket::http::Status;
+use rocket::response::Failure;
+
+use guards::authorization::Actor;
+
+pub mod user_policy;
+
+/// Who can do what with a `Record`, called by the controllers:
+///
+/// ```rust,ignore
+/// UserPolicy::authorize_view(&actor, &user)?;
+/// ```
+///
+/// The `authorize_*` methods fail with 403, answered by the catcher of `handlers.rs`.
+/// A 403 for an existing record and a 404 for a missing one tell which ids exist:
+/// when they must stay hidden, answer 404 instead when `can_view` is false.
+pub trait Policy<Record> {
+    fn can_view(actor: &Actor, record: &Record) -> bool;
+    fn can_edit(actor: &Actor, record: &Record) -> bool;
+    fn can_delete(actor: &Actor, record: &Record) -> bool;
+
+    fn authorize_view(actor: &Actor, record: &Record) -> Result<(), Failure> {
+        authorize(Self::can_view(actor, record))
+    }
+
+    fn authorize_edit(actor: &Actor, record: &Record) -> Result<(), Failure> {
+        authorize(Self::can_edit(actor, record))
+    }
+
+    fn authorize_delete(actor: &Actor, record: &Record) -> Result<(), Failure> {
+        authorize(Self::can_delete(actor, record))
+    }
+}
+
+fn authorize(allowed: bool) -> Result<(), Failure> {
+    if allowed {
+        Ok(())
+    } else {
+        Err(Failure(Status::Forbidden))
+    }
+}
+";
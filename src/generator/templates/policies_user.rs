@@ -0,0 +1,21 @@
+pub static TEXT: &'static str = "use guards::authorization::Actor;
+use models::user::User;
+use super::Policy;
+
+/// Users manage their own account, the `users.*` permissions allow managing the others.
+pub struct UserPolicy;
+
+impl Policy<User> for UserPolicy {
+    fn can_view(actor: &Actor, user: &User) -> bool {
+        actor.user_id == user.id || actor.has_permission(\"users.view\")
+    }
+
+    fn can_edit(actor: &Actor, user: &User) -> bool {
+        actor.user_id == user.id || actor.has_permission(\"users.edit\")
+    }
+
+    fn can_delete(actor: &Actor, user: &User) -> bool {
+        actor.user_id == user.id || actor.has_permission(\"users.delete\")
+    }
+}
+";
@@ -1,4 +1,4 @@
-pub static TEXT: &'static str = "# Hangar
+pub static TEXT: &'static str = "# {{name}}
 Rust web framework, using [Rocket](https://rocket.rs), [Diesel](https://diesel.rs), and [stdweb](https://github.com/koute/stdweb)
 
 ## Installation
@@ -13,7 +13,10 @@ Rust web framework, using [Rocket](https://rocket.rs), [Diesel](https://diesel.r
 ## Running
 ### Server side
 - copy `Rocket.toml.example` to `Rocket.toml`
-- modify `Rocket.toml`
+- modify `Rocket.toml`, or override its settings from `.env` or the environment:
+  `HANGAR_<KEY>` for a setting, `HANGAR_<TABLE>__<KEY>` for a nested one like `HANGAR_MAILER__FROM`,
+  and `<key>_file` to read a secret from a file, e.g. `HANGAR_TOKEN_SECRET_FILE=/run/secrets/token_secret`
+  (`log`, `tls` and `limits` of Rocket only from `Rocket.toml` or `ROCKET_<KEY>`)
 - in server folder `cargo run`
 
 ### Client side
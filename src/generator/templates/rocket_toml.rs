@@ -15,10 +15,36 @@ log = \"normal\"
 # generate using `openssl rand -base64 32`
 secret_key = \"Jd1wnIKl0nRIV7TUJWNHcbn3HqtcaBe3uiVTTfIcGDg=\"
 template_dir = \"src/views/\"
+debug_context = true
 # Application_security
 access_token_timeout_days = 30
+# Only used to verify passwords hashed before per-user salts
 # Don't use this key! generate your own and keep it private!
 password_salt = \"zT0X/fdPU62zSJy3+vvnZg==\"
+# Argon2i parameters, passwords are rehashed on login when these change
+password_hash_passes = 3
+password_hash_lanes = 1
+password_hash_memory_kib = 4096
+password_reset_timeout_hours = 2
+# Signs the links sent by email
+# Don't use this key! generate your own and keep it private!
+token_secret = \"8Yr0UBXjQbTOxfahcVyGcGmc7oVW5Kx4+qxGhwMqpOI=\"
+require_email_confirmation = true
+email_confirmation_timeout_hours = 48
+# Sessions end when idle for too long, or too long after signing in
+session_idle_timeout_minutes = 120
+session_absolute_timeout_days = 30
+# Session activity is written at most once per interval, keep it well under the idle timeout
+activity_refresh_seconds = 60
+# Accounts are locked after too many failed logins, until the unlock link sent by email or the end of the lockout
+lockout_max_failed_attempts = 5
+lockout_duration_minutes = 30
+# Issuer shown by the authenticator apps, and roles which must sign in with a TOTP code
+two_factor_issuer = \"{{name}}\"
+two_factor_required_roles = [\"admin\"]
+# Rate limiting per client IP, `memory` for a single instance, `database` to share it between instances
+rate_limit_store = \"memory\"
+rate_limits = { login = { requests = 5, window_seconds = 60 }, register = { requests = 10, window_seconds = 3600 }, tokens = { requests = 5, window_seconds = 60 } }
 # Assets
 assets_dir = \"src/assets\"
 assets_host = \"localhost:8000\"
@@ -26,10 +52,189 @@ serve_assets = true
 # Database
 database_url = \"{{database_url}}\"
 database_pool = 8
+# Named pools sharing the settings below, `replica` serves `ReadConn` and `primary` replaces `database_url`
+# databases = { replica = { database_url = \"{{database_url}}\", database_pool = 8 } }
+# Connections kept open when idle, and when connections are closed, 0 to keep them
+database_min_idle = 1
+database_connection_timeout_seconds = 30
+database_idle_timeout_seconds = 600
+database_max_lifetime_seconds = 1800
+database_test_on_check_out = true
+# Retries while the database is unreachable at startup, waiting twice as long each time
+database_connect_retries = 2
+database_connect_backoff_ms = 500
 # Logging
 terminal_logger = true
 file_logger = true
-file_logger_dir_path = \"../log/\"
+# Relative to the project root
+file_logger_dir_path = \"log/\"
+# Rotate past a size (0 to disable) and/or `hourly` or `daily`, keeping the most recent rotated files
+log_rotate_size_mb = 10
+log_rotate_interval = \"never\"
+log_rotate_keep = 3
+log_rotate_compress = false
 log_level = \"debug\" # https://docs.rs/slog/2.1.1/slog/enum.Level.html
-mailer = { enabled = true, transport = \"file\", mail_send_dir = \"/tmp\" }
+# `text`, or `json` for one object per event with the request id
+log_format = \"text\"
+# Text access log line: `common`, `combined`, or `:token`s among :host :userid :user :time :method :url :status :size :referer :user_agent :latency :request_id
+access_log_format = \"combined\"
+# Prometheus metrics, only served to these client IPs unless the list is empty
+metrics_enabled = true
+metrics_path = \"/metrics\"
+metrics_allowed_ips = [\"127.0.0.1\", \"::1\"]
+mailer = { enabled = true, transport = \"file\", mail_send_dir = \"/tmp\", from = \"noreply@localhost\", base_url = \"http://localhost:8000\" }
+
+[staging]
+address = \"0.0.0.0\"
+port = 8000
+workers = 8
+log = \"normal\"
+# generated by `hangar new`, keep it private!
+secret_key = \"{{secret_key_staging}}\"
+template_dir = \"src/views/\"
+debug_context = false
+# Application_security
+access_token_timeout_days = 30
+# Only used to verify passwords hashed before per-user salts
+# generated by `hangar new`, keep it private!
+password_salt = \"{{password_salt_staging}}\"
+# Argon2i parameters, passwords are rehashed on login when these change
+password_hash_passes = 3
+password_hash_lanes = 1
+password_hash_memory_kib = 4096
+password_reset_timeout_hours = 2
+# Signs the links sent by email
+# generated by `hangar new`, keep it private!
+token_secret = \"{{token_secret_staging}}\"
+require_email_confirmation = true
+email_confirmation_timeout_hours = 48
+# Sessions end when idle for too long, or too long after signing in
+session_idle_timeout_minutes = 120
+session_absolute_timeout_days = 30
+# Session activity is written at most once per interval, keep it well under the idle timeout
+activity_refresh_seconds = 60
+# Accounts are locked after too many failed logins, until the unlock link sent by email or the end of the lockout
+lockout_max_failed_attempts = 5
+lockout_duration_minutes = 30
+# Issuer shown by the authenticator apps, and roles which must sign in with a TOTP code
+two_factor_issuer = \"{{name}}\"
+two_factor_required_roles = [\"admin\"]
+# Rate limiting per client IP, `memory` for a single instance, `database` to share it between instances
+rate_limit_store = \"database\"
+rate_limits = { login = { requests = 5, window_seconds = 60 }, register = { requests = 10, window_seconds = 3600 }, tokens = { requests = 5, window_seconds = 60 } }
+# Assets
+assets_dir = \"src/assets\"
+assets_host = \"localhost:8000\"
+serve_assets = false
+# Database
+database_url = \"{{database_url_staging}}\"
+database_pool = 16
+# Named pools sharing the settings below, `replica` serves `ReadConn` and `primary` replaces `database_url`
+# databases = { replica = { database_url = \"{{database_url_staging}}\", database_pool = 8 } }
+# Connections kept open when idle, and when connections are closed, 0 to keep them
+database_min_idle = 2
+database_connection_timeout_seconds = 30
+database_idle_timeout_seconds = 600
+database_max_lifetime_seconds = 1800
+database_test_on_check_out = true
+# Retries while the database is unreachable at startup, waiting twice as long each time
+database_connect_retries = 5
+database_connect_backoff_ms = 500
+# Logging
+terminal_logger = true
+file_logger = true
+# Relative to the project root
+file_logger_dir_path = \"log/\"
+# Rotate past a size (0 to disable) and/or `hourly` or `daily`, keeping the most recent rotated files
+log_rotate_size_mb = 100
+log_rotate_interval = \"daily\"
+log_rotate_keep = 7
+log_rotate_compress = true
+log_level = \"Info\" # https://docs.rs/slog/2.1.1/slog/enum.Level.html
+# `text`, or `json` for one object per event with the request id
+log_format = \"json\"
+# Text access log line: `common`, `combined`, or `:token`s among :host :userid :user :time :method :url :status :size :referer :user_agent :latency :request_id
+access_log_format = \"combined\"
+# Prometheus metrics, only served to these client IPs unless the list is empty
+metrics_enabled = true
+metrics_path = \"/metrics\"
+metrics_allowed_ips = [\"127.0.0.1\", \"::1\"]
+mailer = { enabled = true, transport = \"sendmail\", from = \"noreply@example.com\", base_url = \"https://example.com\" }
+
+[production]
+address = \"0.0.0.0\"
+port = 8000
+workers = 16
+log = \"critical\"
+# generated by `hangar new`, keep it private!
+secret_key = \"{{secret_key_production}}\"
+template_dir = \"src/views/\"
+debug_context = false
+# Application_security
+access_token_timeout_days = 30
+# Only used to verify passwords hashed before per-user salts
+# generated by `hangar new`, keep it private!
+password_salt = \"{{password_salt_production}}\"
+# Argon2i parameters, passwords are rehashed on login when these change
+password_hash_passes = 3
+password_hash_lanes = 1
+password_hash_memory_kib = 4096
+password_reset_timeout_hours = 2
+# Signs the links sent by email
+# generated by `hangar new`, keep it private!
+token_secret = \"{{token_secret_production}}\"
+require_email_confirmation = true
+email_confirmation_timeout_hours = 48
+# Sessions end when idle for too long, or too long after signing in
+session_idle_timeout_minutes = 120
+session_absolute_timeout_days = 30
+# Session activity is written at most once per interval, keep it well under the idle timeout
+activity_refresh_seconds = 60
+# Accounts are locked after too many failed logins, until the unlock link sent by email or the end of the lockout
+lockout_max_failed_attempts = 5
+lockout_duration_minutes = 30
+# Issuer shown by the authenticator apps, and roles which must sign in with a TOTP code
+two_factor_issuer = \"{{name}}\"
+two_factor_required_roles = [\"admin\"]
+# Rate limiting per client IP, `memory` for a single instance, `database` to share it between instances
+rate_limit_store = \"database\"
+rate_limits = { login = { requests = 5, window_seconds = 60 }, register = { requests = 10, window_seconds = 3600 }, tokens = { requests = 5, window_seconds = 60 } }
+# Assets
+assets_dir = \"src/assets\"
+assets_host = \"localhost:8000\"
+serve_assets = false
+# Database
+database_url = \"{{database_url_production}}\"
+database_pool = 32
+# Named pools sharing the settings below, `replica` serves `ReadConn` and `primary` replaces `database_url`
+# databases = { replica = { database_url = \"{{database_url_production}}\", database_pool = 8 } }
+# Connections kept open when idle, and when connections are closed, 0 to keep them
+database_min_idle = 4
+database_connection_timeout_seconds = 30
+database_idle_timeout_seconds = 600
+database_max_lifetime_seconds = 1800
+database_test_on_check_out = true
+# Retries while the database is unreachable at startup, waiting twice as long each time
+database_connect_retries = 10
+database_connect_backoff_ms = 500
+# Logging
+terminal_logger = false
+file_logger = true
+# Relative to the project root
+file_logger_dir_path = \"log/\"
+# Rotate past a size (0 to disable) and/or `hourly` or `daily`, keeping the most recent rotated files
+log_rotate_size_mb = 100
+log_rotate_interval = \"daily\"
+log_rotate_keep = 14
+log_rotate_compress = true
+log_level = \"Warning\" # https://docs.rs/slog/2.1.1/slog/enum.Level.html
+# `text`, or `json` for one object per event with the request id
+log_format = \"json\"
+# Text access log line: `common`, `combined`, or `:token`s among :host :userid :user :time :method :url :status :size :referer :user_agent :latency :request_id
+access_log_format = \"combined\"
+# Prometheus metrics, only served to these client IPs unless the list is empty
+metrics_enabled = true
+metrics_path = \"/metrics\"
+metrics_allowed_ips = [\"127.0.0.1\", \"::1\"]
+mailer = { enabled = true, transport = \"sendmail\", from = \"noreply@example.com\", base_url = \"https://example.com\" }
 ";
@@ -1,6 +1,7 @@
-pub static TEXT: &'static str = "#![feature(plugin, const_fn)]
+pub static TEXT: &'static str = "#![feature(plugin, const_fn, decl_macro, custom_derive)]
 #![feature(core_intrinsics)]
 #![plugin(stainless)]
+#![plugin(rocket_codegen)]
 
 extern crate rocket;
 #[macro_use] extern crate rocket_contrib;
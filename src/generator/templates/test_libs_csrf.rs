@@ -0,0 +1,95 @@
+pub static TEXT: &'static str = "use rocket;
+use rocket::fairing::AdHoc;
+use rocket::http::{ContentType, Status};
+use rocket::local::Client;
+use rocket::request::Form;
+
+use hangar::libs::csrf::{self, form_token, multipart_token, CsrfToken};
+
+#[derive(FromForm)]
+struct Comment {
+    body: String,
+}
+
+// Like the forms of the app, without `LenientForm` or anything else checking the token
+#[post(\"/comments\", data = \"<comment>\")]
+fn create_comment(comment: Form<Comment>) -> String {
+    comment.into_inner().body
+}
+
+#[get(\"/token\")]
+fn token(csrf_token: CsrfToken) -> String {
+    csrf_token.as_str().to_string()
+}
+
+fn client() -> Client {
+    let rocket = rocket::ignite()
+        .attach(AdHoc::on_request(|request, data| csrf::protect(request, data)))
+        .mount(\"/\", routes![create_comment, token, csrf::failure]);
+    Client::new(rocket).unwrap()
+}
+
+fn post_comment(client: &Client, body: &str) -> Status {
+    client
+        .post(\"/comments\")
+        .header(ContentType::Form)
+        .body(body)
+        .dispatch()
+        .status()
+}
+
+#[test]
+fn protect_rejects_forms_without_a_token() {
+    let client = client();
+    assert_eq!(post_comment(&client, \"body=hello\"), Status::Forbidden);
+}
+
+#[test]
+fn protect_rejects_forms_with_a_wrong_token() {
+    let client = client();
+    client.get(\"/token\").dispatch();
+    assert_eq!(post_comment(&client, \"csrf_token=0a1b2c&body=hello\"), Status::Forbidden);
+}
+
+#[test]
+fn protect_accepts_forms_with_the_session_token() {
+    let client = client();
+    let token = client.get(\"/token\").dispatch().body_string().unwrap();
+    let body = format!(\"csrf_token={}&body=hello\", token);
+    assert_eq!(post_comment(&client, &body), Status::Ok);
+}
+
+#[test]
+fn form_token_reads_the_first_field() {
+    assert_eq!(
+        form_token(\"csrf_token=0a1b2c&email=user%40example.com\"),
+        Some(String::from(\"0a1b2c\"))
+    );
+}
+
+#[test]
+fn form_token_reads_any_field() {
+    assert_eq!(
+        form_token(\"email=user%40example.com&password=secret&csrf_token=0a1b2c\"),
+        Some(String::from(\"0a1b2c\"))
+    );
+}
+
+#[test]
+fn form_token_is_none_without_the_field() {
+    assert_eq!(form_token(\"email=user%40example.com&password=secret\"), None);
+    assert_eq!(form_token(\"my_csrf_token=0a1b2c&csrf_token_x=0a1b2c\"), None);
+    assert_eq!(form_token(\"\"), None);
+}
+
+#[test]
+fn multipart_token_reads_the_token_part() {
+    let body = \"--boundary\\r\\n\\
+                Content-Disposition: form-data; name=\\\"csrf_token\\\"\\r\\n\\r\\n\\
+                0a1b2c\\r\\n\\
+                --boundary\\r\\n\\
+                Content-Disposition: form-data; name=\\\"avatar\\\"; filename=\\\"a.png\\\"\\r\\n\";
+    assert_eq!(multipart_token(body), Some(String::from(\"0a1b2c\")));
+    assert_eq!(multipart_token(\"--boundary\\r\\n\"), None);
+}
+";
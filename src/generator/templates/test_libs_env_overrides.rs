@@ -0,0 +1,56 @@
+pub static TEXT: &'static str = "use std::env;
+use std::fs;
+
+use rocket::config::{Config, Environment};
+
+use hangar::libs::env_overrides::{apply, apply_rocket_settings};
+use hangar::libs::settings_validation::validate;
+
+const SECRET_KEY: &str = \"VuxdEJzESwQshHmIB/m50whROQzim+d6BNcHwZC4gYc=\";
+
+fn staging_config(secret_key_path: &str) -> Config {
+    Config::build(Environment::Staging)
+        .extra(\"database_url\", \"db/test.sqlite\")
+        .extra(\"password_salt\", \"a salt of staging\")
+        .extra(\"token_secret\", \"a secret of staging\")
+        .extra(\"secret_key_file\", secret_key_path)
+        .finalize()
+        .unwrap()
+}
+
+#[test]
+fn secret_key_file_sets_the_secret_key_of_rocket() {
+    let path = env::temp_dir().join(\"hangar_test_secret_key\");
+    fs::write(&path, format!(\"{}\\n\", SECRET_KEY)).unwrap();
+    let mut config = staging_config(path.to_str().unwrap());
+
+    assert_eq!(apply(&mut config), Ok(()));
+    assert!(!config.extras.contains_key(\"secret_key_file\"));
+    assert_eq!(config.get_str(\"secret_key\").ok(), Some(SECRET_KEY));
+    assert_eq!(validate(&config), Ok(()));
+    assert_eq!(apply_rocket_settings(&mut config), Ok(()));
+    assert!(!config.extras.contains_key(\"secret_key\"));
+}
+
+#[test]
+fn secret_key_file_must_be_readable() {
+    let mut config = staging_config(\"missing/secret_key\");
+
+    let errors = apply(&mut config).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with(\"`secret_key_file` can't read `missing/secret_key`\"));
+}
+
+#[test]
+fn apply_rocket_settings_refuses_invalid_secret_keys() {
+    let mut config = Config::build(Environment::Development)
+        .extra(\"secret_key\", \"too short\")
+        .finalize()
+        .unwrap();
+
+    assert_eq!(
+        apply_rocket_settings(&mut config),
+        Err(vec![String::from(\"`secret_key` is not valid for Rocket\")])
+    );
+}
+";
@@ -0,0 +1,5 @@
+pub static TEXT: &'static str = "mod csrf;
+mod env_overrides;
+mod settings_validation;
+mod totp;
+";
@@ -0,0 +1,83 @@
+pub static TEXT: &'static str = "use rocket::config::{Config, Environment, Value};
+
+use hangar::libs::settings_validation::validate;
+
+const DATABASE_URL: &str = \"db/test.sqlite\";
+
+#[test]
+fn validate_accepts_choices_in_any_case() {
+    let config = Config::build(Environment::Development)
+        .extra(\"database_url\", DATABASE_URL)
+        .extra(\"log_level\", \"DEBUG\")
+        .extra(\"log_format\", \"Json\")
+        .extra(\"log_rotate_interval\", \"Daily\")
+        .extra(\"rate_limit_store\", \"Database\")
+        .finalize()
+        .unwrap();
+    assert_eq!(validate(&config), Ok(()));
+}
+
+#[test]
+fn validate_refuses_unknown_choices() {
+    let config = Config::build(Environment::Development)
+        .extra(\"database_url\", DATABASE_URL)
+        .extra(\"log_level\", \"verbose\")
+        .finalize()
+        .unwrap();
+    assert_eq!(
+        validate(&config),
+        Err(vec![String::from(
+            \"`log_level` must be one of critical, error, warning, info, debug, trace, found `verbose`\"
+        )])
+    );
+}
+
+#[test]
+fn validate_reports_unknown_keys() {
+    let config = Config::build(Environment::Development)
+        .extra(\"databse_url\", DATABASE_URL)
+        .finalize()
+        .unwrap();
+    assert_eq!(
+        validate(&config),
+        Err(vec![
+            String::from(\"`databse_url` is not a known setting\"),
+            String::from(\"`database_url` must be set\"),
+        ])
+    );
+}
+
+#[test]
+fn validate_requires_secrets_outside_of_development() {
+    let config = Config::build(Environment::Staging)
+        .extra(\"database_url\", DATABASE_URL)
+        .extra(\"password_salt\", \"zT0X/fdPU62zSJy3+vvnZg==\")
+        .finalize()
+        .unwrap();
+    let errors = validate(&config).unwrap_err();
+    assert!(errors.contains(&String::from(
+        \"`password_salt` must be set to a secret of your own in staging\"
+    )));
+    assert!(errors.contains(&String::from(
+        \"`token_secret` must be set to a secret of your own in staging\"
+    )));
+}
+
+#[test]
+fn validate_refuses_more_idle_connections_than_a_named_pool() {
+    let databases = \"replica = { database_url = \\\"db/replica.sqlite\\\", database_pool = 2, database_min_idle = 4 }\"
+        .parse::<Value>()
+        .unwrap();
+    let config = Config::build(Environment::Development)
+        .extra(\"database_url\", DATABASE_URL)
+        .extra(\"databases\", databases)
+        .finalize()
+        .unwrap();
+    assert_eq!(
+        validate(&config),
+        Err(vec![String::from(
+            \"`databases.replica.database_min_idle` must not be above its `database_pool` (2), found 4\"
+        )])
+    );
+}
+";
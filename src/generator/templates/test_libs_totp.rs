@@ -0,0 +1,50 @@
+pub static TEXT: &'static str = "use hangar::libs::totp::{base32_decode, base32_encode, code_at, provisioning_uri, verify_code};
+
+// Secret of the SHA1 test vectors of RFC 6238, appendix B
+const RFC_6238_SECRET: &[u8] = b\"12345678901234567890\";
+
+#[test]
+fn code_at_matches_rfc_6238_vectors() {
+    let vectors = [
+        (59, \"287082\"),
+        (1111111109, \"081804\"),
+        (1111111111, \"050471\"),
+        (1234567890, \"005924\"),
+        (2000000000, \"279037\"),
+    ];
+    for &(time, code) in vectors.iter() {
+        assert_eq!(code_at(RFC_6238_SECRET, time / 30), code);
+    }
+}
+
+#[test]
+fn verify_code_accepts_one_step_of_drift() {
+    let secret = base32_encode(RFC_6238_SECRET);
+    assert_eq!(verify_code(&secret, \"287082\", 59, None), Some(1));
+    assert_eq!(verify_code(&secret, \"287082\", 89, None), Some(1));
+    assert_eq!(verify_code(&secret, \"287082\", 149, None), None);
+    assert_eq!(verify_code(&secret, \"000000\", 59, None), None);
+}
+
+#[test]
+fn verify_code_refuses_replayed_steps() {
+    let secret = base32_encode(RFC_6238_SECRET);
+    assert_eq!(verify_code(&secret, \"287082\", 59, Some(1)), None);
+}
+
+#[test]
+fn base32_round_trips() {
+    assert_eq!(base32_encode(b\"foobar\"), \"MZXW6YTBOI\");
+    assert_eq!(base32_decode(\"mzxw6ytboi\"), Some(b\"foobar\".to_vec()));
+    assert_eq!(base32_decode(\"not base32!\"), None);
+}
+
+#[test]
+fn provisioning_uri_escapes_the_label() {
+    let uri = provisioning_uri(\"MZXW6YTBOI\", \"user@example.com\", \"My App\");
+    assert_eq!(
+        uri,
+        \"otpauth://totp/My%20App:user%40example.com?secret=MZXW6YTBOI&issuer=My%20App&algorithm=SHA1&digits=6&period=30\"
+    );
+}
+";
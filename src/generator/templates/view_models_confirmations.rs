@@ -0,0 +1,3 @@
+pub static TEXT: &'static str = "#[derive(Serialize)]
+pub struct ConfirmationNew {}
+";
@@ -1,10 +1,20 @@
 pub static TEXT: &'static str = "use std::default::Default;
+use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
 use serde::Serialize;
 use serde_json::Map;
 use serde_json::value::Value;
 
+use libs::csrf::CsrfToken;
+
+pub mod confirmations;
+pub mod password_resets;
+pub mod sessions;
+pub mod two_factor;
 pub mod users;
 
+/// Whether the templates may dump their context for debugging, set from `debug_context` in `Rocket.toml`.
+pub static DEBUG_CONTEXT: AtomicBool = ATOMIC_BOOL_INIT;
+
 #[derive(Debug, Serialize)]
 pub struct DefaultBody {
     pub content: String,
@@ -26,7 +36,7 @@ pub struct DefaultHeader {
 impl Default for DefaultHeader {
     fn default() -> DefaultHeader {
         DefaultHeader {
-            title: String::from(\"Hangar\"),
+            title: String::from(\"{{name}}\"),
         }
     }
 }
@@ -50,8 +60,21 @@ where
     B: Serialize,
 {
     pub fn new(header: H, body: B) -> Self {
-        let payload: Map<String, Value> = Map::new();
+        let mut payload: Map<String, Value> = Map::new();
+        payload.insert(
+            String::from(\"debug_context\"),
+            Value::Bool(DEBUG_CONTEXT.load(Ordering::Relaxed)),
+        );
         ViewModel { header, body, payload, }
     }
+
+    /// Set `payload.csrf_token`, for the forms of the template.
+    pub fn csrf_token(mut self, token: &CsrfToken) -> Self {
+        self.payload.insert(
+            String::from(\"csrf_token\"),
+            Value::String(token.as_str().to_string()),
+        );
+        self
+    }
 }
 ";
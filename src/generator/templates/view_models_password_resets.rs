@@ -0,0 +1,7 @@
+pub static TEXT: &'static str = "#[derive(Serialize)]
+pub struct PasswordResetNew {}
+#[derive(Serialize)]
+pub struct PasswordResetEdit {
+    pub token: String,
+}
+";
@@ -0,0 +1,8 @@
+pub static TEXT: &'static str = "use models::session::Session;
+
+#[derive(Serialize)]
+pub struct SessionIndex {
+    pub current_session_id: i32,
+    pub sessions: Vec<Session>,
+}
+";
@@ -0,0 +1,14 @@
+pub static TEXT: &'static str = "#[derive(Serialize)]
+pub struct TwoFactorNew {
+    pub enabled: bool,
+    pub secret: Option<String>,
+    pub provisioning_uri: Option<String>,
+    pub qr_code_svg: Option<String>,
+}
+#[derive(Serialize)]
+pub struct TwoFactorVerify {}
+#[derive(Serialize)]
+pub struct RecoveryCodes {
+    pub codes: Vec<String>,
+}
+";
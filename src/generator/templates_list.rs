@@ -9,30 +9,71 @@ lazy_static! {
         m.insert("client/assets/application.js", templates::application_js::TEXT);
         m.insert("log/.gitkeep", templates::blank::TEXT);
         m.insert("migrations/.gitkeep", templates::blank::TEXT);
-        m.insert("src/apis/mod.rs", templates::blank::TEXT);
+        m.insert("src/apis/health_api.rs", templates::apis_health::TEXT);
+        m.insert("src/apis/mod.rs", templates::apis_mod::TEXT);
+        m.insert("src/apis/tokens_api.rs", templates::apis_tokens::TEXT);
         m.insert("src/assets/favicon.svg", templates::favicon_svg::TEXT);
         m.insert("src/controllers/assets_controller.rs", templates::controller_assets::TEXT);
+        m.insert("src/controllers/confirmations_controller.rs", templates::controller_confirmations::TEXT);
+        m.insert("src/controllers/metrics_controller.rs", templates::controller_metrics::TEXT);
         m.insert("src/controllers/mod.rs", templates::controller_mod::TEXT);
         m.insert("src/controllers/pages_controller.rs", templates::controller_pages::TEXT);
+        m.insert("src/controllers/password_resets_controller.rs", templates::controller_password_resets::TEXT);
+        m.insert("src/controllers/sessions_controller.rs", templates::controller_sessions::TEXT);
+        m.insert("src/controllers/two_factor_controller.rs", templates::controller_two_factor::TEXT);
+        m.insert("src/controllers/unlocks_controller.rs", templates::controller_unlocks::TEXT);
         m.insert("src/controllers/users_controller.rs", templates::controller_users::TEXT);
+        m.insert("src/guards/authorization.rs", templates::guards_authorization::TEXT);
         m.insert("src/guards/mod.rs", templates::guards_mod::TEXT);
+        m.insert("src/guards/password_reset.rs", templates::guards_password_reset::TEXT);
+        m.insert("src/guards/session.rs", templates::guards_session::TEXT);
+        m.insert("src/guards/two_factor.rs", templates::guards_two_factor::TEXT);
         m.insert("src/guards/user.rs", templates::guards_user::TEXT);
         m.insert("src/guards/sanitized_str.rs", templates::guards_sanitized_str::TEXT);
         m.insert("src/libs/conn.rs", templates::libs_conn::TEXT);
+        m.insert("src/libs/csrf.rs", templates::libs_csrf::TEXT);
         m.insert("src/libs/lib_const.rs", templates::libs_const::TEXT);
         m.insert("src/libs/email.rs", templates::libs_email::TEXT);
+        m.insert("src/libs/email_disabled.rs", templates::libs_email_disabled::TEXT);
+        m.insert("src/libs/mailer_config.rs", templates::libs_mailer_config::TEXT);
+        m.insert("src/libs/env_overrides.rs", templates::libs_env_overrides::TEXT);
         m.insert("src/libs/init.rs", templates::libs_init::TEXT);
         m.insert("src/libs/log_format.rs", templates::libs_log_format::TEXT);
+        m.insert("src/libs/log_rotation.rs", templates::libs_log_rotation::TEXT);
         m.insert("src/libs/logger.rs", templates::libs_logger::TEXT);
+        m.insert("src/libs/metrics.rs", templates::libs_metrics::TEXT);
         m.insert("src/libs/mod.rs", templates::libs_mod::TEXT);
+        m.insert("src/libs/password.rs", templates::libs_password::TEXT);
+        m.insert("src/libs/rate_limit.rs", templates::libs_rate_limit::TEXT);
+        m.insert("src/libs/request_log.rs", templates::libs_request_log::TEXT);
         m.insert("src/libs/routes.rs", templates::libs_routes::TEXT);
         m.insert("src/libs/settings.rs", templates::libs_settings::TEXT);
+        m.insert("src/libs/settings_validation.rs", templates::libs_settings_validation::TEXT);
+        m.insert("src/libs/token.rs", templates::libs_token::TEXT);
+        m.insert("src/libs/totp.rs", templates::libs_totp::TEXT);
         m.insert("src/models/mod.rs", templates::model_mod::TEXT);
+        m.insert("src/models/password_reset.rs", templates::model_password_reset::TEXT);
+        m.insert("src/models/recovery_code.rs", templates::model_recovery_code::TEXT);
+        m.insert("src/models/role.rs", templates::model_role::TEXT);
+        m.insert("src/models/session.rs", templates::model_session::TEXT);
+        m.insert("src/policies/mod.rs", templates::policies_mod::TEXT);
+        m.insert("src/policies/user_policy.rs", templates::policies_user::TEXT);
+        m.insert("src/view_models/confirmations.rs", templates::view_models_confirmations::TEXT);
         m.insert("src/view_models/mod.rs", templates::view_models_mod::TEXT);
+        m.insert("src/view_models/sessions.rs", templates::view_models_sessions::TEXT);
+        m.insert("src/view_models/two_factor.rs", templates::view_models_two_factor::TEXT);
+        m.insert("src/view_models/password_resets.rs", templates::view_models_password_resets::TEXT);
         m.insert("src/view_models/users.rs", templates::view_models_users::TEXT);
         m.insert("src/views/pages/404.html.tera", templates::html_404::TEXT);
         m.insert("src/views/pages/index.html.tera", templates::html_index::TEXT);
         m.insert("src/views/base_layout.html.tera", templates::html_base_layout::TEXT);
+        m.insert("src/views/confirmations/new.html.tera", templates::html_confirmations_new::TEXT);
+        m.insert("src/views/password_resets/edit.html.tera", templates::html_password_resets_edit::TEXT);
+        m.insert("src/views/password_resets/new.html.tera", templates::html_password_resets_new::TEXT);
+        m.insert("src/views/sessions/index.html.tera", templates::html_sessions_index::TEXT);
+        m.insert("src/views/two_factor/new.html.tera", templates::html_two_factor_new::TEXT);
+        m.insert("src/views/two_factor/recovery_codes.html.tera", templates::html_two_factor_recovery_codes::TEXT);
+        m.insert("src/views/two_factor/verify.html.tera", templates::html_two_factor_verify::TEXT);
         m.insert("src/views/users/show.html.tera", templates::html_users_show::TEXT);
         m.insert("src/views/users/signin.html.tera", templates::html_users_signin::TEXT);
         m.insert("src/views/users/signup.html.tera", templates::html_users_signup::TEXT);
@@ -41,15 +82,21 @@ lazy_static! {
         m.insert("src/lib.rs", templates::lib::TEXT);
         m.insert("src/main.rs", templates::main::TEXT);
         m.insert("tests/lib.rs", templates::test_lib::TEXT);
+        m.insert("tests/libs/mod.rs", templates::test_libs_mod::TEXT);
+        m.insert("tests/libs/csrf.rs", templates::test_libs_csrf::TEXT);
+        m.insert(
+            "tests/libs/env_overrides.rs",
+            templates::test_libs_env_overrides::TEXT,
+        );
+        m.insert(
+            "tests/libs/settings_validation.rs",
+            templates::test_libs_settings_validation::TEXT,
+        );
+        m.insert("tests/libs/totp.rs", templates::test_libs_totp::TEXT);
         m.insert(".gitignore", templates::gitignore::TEXT);
         m.insert("README.md", templates::readme_md::TEXT);
         m.insert("rustfmt.toml", templates::rustfmt_toml::TEXT);
         m.insert("watch.sh", templates::watch_sh::TEXT);
-        m
-    };
-
-    pub static ref VAR_TEMPLATES: HashMap<&'static str, &'static str> = {
-        let mut m = HashMap::new();
         m.insert(".env", templates::dotenv::TEXT);
         m.insert("Cargo.toml", templates::cargo_toml::TEXT);
         m.insert("Rocket.toml", templates::rocket_toml::TEXT);
@@ -63,6 +110,22 @@ lazy_static! {
         m.insert("src/models/user.rs", templates::model_user::TEXT);
         m.insert("migrations/00000000000001_create_users/down.sql", templates::db_sqlite::DOWN);
         m.insert("migrations/00000000000001_create_users/up.sql", templates::db_sqlite::UP);
+        m.insert("migrations/00000000000002_password_hashes/down.sql", templates::db_sqlite::DOWN_02);
+        m.insert("migrations/00000000000002_password_hashes/up.sql", templates::db_sqlite::UP_02);
+        m.insert("migrations/00000000000003_create_password_resets/down.sql", templates::db_sqlite::DOWN_03);
+        m.insert("migrations/00000000000003_create_password_resets/up.sql", templates::db_sqlite::UP_03);
+        m.insert("migrations/00000000000004_add_confirmed_at/down.sql", templates::db_sqlite::DOWN_04);
+        m.insert("migrations/00000000000004_add_confirmed_at/up.sql", templates::db_sqlite::UP_04);
+        m.insert("migrations/00000000000005_create_sessions/down.sql", templates::db_sqlite::DOWN_05);
+        m.insert("migrations/00000000000005_create_sessions/up.sql", templates::db_sqlite::UP_05);
+        m.insert("migrations/00000000000006_create_roles/down.sql", templates::db_sqlite::DOWN_06);
+        m.insert("migrations/00000000000006_create_roles/up.sql", templates::db_sqlite::UP_06);
+        m.insert("migrations/00000000000007_create_rate_limits/down.sql", templates::db_sqlite::DOWN_07);
+        m.insert("migrations/00000000000007_create_rate_limits/up.sql", templates::db_sqlite::UP_07);
+        m.insert("migrations/00000000000008_add_lockout/down.sql", templates::db_sqlite::DOWN_08);
+        m.insert("migrations/00000000000008_add_lockout/up.sql", templates::db_sqlite::UP_08);
+        m.insert("migrations/00000000000009_add_two_factor/down.sql", templates::db_sqlite::DOWN_09);
+        m.insert("migrations/00000000000009_add_two_factor/up.sql", templates::db_sqlite::UP_09);
         m
     };
 
@@ -71,6 +134,22 @@ lazy_static! {
         m.insert("src/models/user.rs", templates::model_user::TEXT);
         m.insert("migrations/00000000000001_create_users/down.sql", templates::db_mysql::DOWN);
         m.insert("migrations/00000000000001_create_users/up.sql", templates::db_mysql::UP);
+        m.insert("migrations/00000000000002_password_hashes/down.sql", templates::db_mysql::DOWN_02);
+        m.insert("migrations/00000000000002_password_hashes/up.sql", templates::db_mysql::UP_02);
+        m.insert("migrations/00000000000003_create_password_resets/down.sql", templates::db_mysql::DOWN_03);
+        m.insert("migrations/00000000000003_create_password_resets/up.sql", templates::db_mysql::UP_03);
+        m.insert("migrations/00000000000004_add_confirmed_at/down.sql", templates::db_mysql::DOWN_04);
+        m.insert("migrations/00000000000004_add_confirmed_at/up.sql", templates::db_mysql::UP_04);
+        m.insert("migrations/00000000000005_create_sessions/down.sql", templates::db_mysql::DOWN_05);
+        m.insert("migrations/00000000000005_create_sessions/up.sql", templates::db_mysql::UP_05);
+        m.insert("migrations/00000000000006_create_roles/down.sql", templates::db_mysql::DOWN_06);
+        m.insert("migrations/00000000000006_create_roles/up.sql", templates::db_mysql::UP_06);
+        m.insert("migrations/00000000000007_create_rate_limits/down.sql", templates::db_mysql::DOWN_07);
+        m.insert("migrations/00000000000007_create_rate_limits/up.sql", templates::db_mysql::UP_07);
+        m.insert("migrations/00000000000008_add_lockout/down.sql", templates::db_mysql::DOWN_08);
+        m.insert("migrations/00000000000008_add_lockout/up.sql", templates::db_mysql::UP_08);
+        m.insert("migrations/00000000000009_add_two_factor/down.sql", templates::db_mysql::DOWN_09);
+        m.insert("migrations/00000000000009_add_two_factor/up.sql", templates::db_mysql::UP_09);
         m
     };
 
@@ -81,6 +160,22 @@ lazy_static! {
         m.insert("migrations/00000000000000_diesel_initial_setup/up.sql", templates::db_postgresql::UP_00);
         m.insert("migrations/00000000000001_create_users/down.sql", templates::db_postgresql::DOWN_01);
         m.insert("migrations/00000000000001_create_users/up.sql", templates::db_postgresql::UP_01);
+        m.insert("migrations/00000000000002_password_hashes/down.sql", templates::db_postgresql::DOWN_02);
+        m.insert("migrations/00000000000002_password_hashes/up.sql", templates::db_postgresql::UP_02);
+        m.insert("migrations/00000000000003_create_password_resets/down.sql", templates::db_postgresql::DOWN_03);
+        m.insert("migrations/00000000000003_create_password_resets/up.sql", templates::db_postgresql::UP_03);
+        m.insert("migrations/00000000000004_add_confirmed_at/down.sql", templates::db_postgresql::DOWN_04);
+        m.insert("migrations/00000000000004_add_confirmed_at/up.sql", templates::db_postgresql::UP_04);
+        m.insert("migrations/00000000000005_create_sessions/down.sql", templates::db_postgresql::DOWN_05);
+        m.insert("migrations/00000000000005_create_sessions/up.sql", templates::db_postgresql::UP_05);
+        m.insert("migrations/00000000000006_create_roles/down.sql", templates::db_postgresql::DOWN_06);
+        m.insert("migrations/00000000000006_create_roles/up.sql", templates::db_postgresql::UP_06);
+        m.insert("migrations/00000000000007_create_rate_limits/down.sql", templates::db_postgresql::DOWN_07);
+        m.insert("migrations/00000000000007_create_rate_limits/up.sql", templates::db_postgresql::UP_07);
+        m.insert("migrations/00000000000008_add_lockout/down.sql", templates::db_postgresql::DOWN_08);
+        m.insert("migrations/00000000000008_add_lockout/up.sql", templates::db_postgresql::UP_08);
+        m.insert("migrations/00000000000009_add_two_factor/down.sql", templates::db_postgresql::DOWN_09);
+        m.insert("migrations/00000000000009_add_two_factor/up.sql", templates::db_postgresql::UP_09);
         m
     };
 }
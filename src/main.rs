@@ -1,7 +1,9 @@
 extern crate clap;
 #[macro_use]
 extern crate lazy_static;
+extern crate regex;
 extern crate tera;
+extern crate toml;
 
 use std::process::exit;
 use std::env;
@@ -10,10 +12,13 @@ use clap::{App, AppSettings, Arg, SubCommand};
 
 mod build;
 mod db;
+mod generate;
 mod help;
 mod new;
+mod plugin;
 mod run;
 mod generator;
+mod project;
 mod watch;
 
 fn main() {
@@ -21,6 +26,7 @@ fn main() {
 
     let build_subcommand = generate_build_subcommand();
     let db_subcommand = generate_db_subcommand();
+    let generate_subcommand = generate_generate_subcommand();
     let new_subcommand = generate_new_subcommand();
     let run_subcommand = generate_run_subcommand();
 
@@ -28,21 +34,38 @@ fn main() {
         .version(env!("CARGO_PKG_VERSION"))
         .author("Karuna Murti <karuna.murti@gmail.com>")
         .setting(AppSettings::ArgRequiredElseHelp)
+        .setting(AppSettings::AllowExternalSubcommands)
+        .arg(
+            Arg::with_name("list")
+                .long("list")
+                .help("List installed commands, including `hangar-*` plugins in PATH")
+        )
         .subcommand(build_subcommand)
         .subcommand(db_subcommand)
+        .subcommand(generate_subcommand)
         .subcommand(new_subcommand)
         .subcommand(run_subcommand)
         .get_matches_from(&args);
 
-    let result: Result<String, String> = if let Some(matches) = matches.subcommand_matches("build")
+    let result: Result<String, String> = if matches.is_present("list") {
+        plugin::list()
+    } else if let Some(matches) = matches.subcommand_matches("build")
     {
         build::execute(&args)
     } else if let Some(matches) = matches.subcommand_matches("db") {
         db::execute(&args)
+    } else if let Some(matches) = matches.subcommand_matches("generate") {
+        generate::execute(matches)
     } else if let Some(matches) = matches.subcommand_matches("new") {
         new::execute(&matches)
     } else if let Some(matches) = matches.subcommand_matches("run") {
         run::execute(&args)
+    } else if let (name, Some(matches)) = matches.subcommand() {
+        let plugin_args: Vec<&str> = matches
+            .values_of("")
+            .map(|values| values.collect())
+            .unwrap_or_default();
+        plugin::execute(name, &plugin_args)
     } else {
         return;
     };
@@ -84,6 +107,30 @@ fn generate_db_subcommand<'a, 'b>() -> App<'a, 'b> {
         .setting(AppSettings::AllowExternalSubcommands)
 }
 
+fn generate_generate_subcommand<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("generate")
+        .about("Generate files for an existing Hangar Project")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("deployment")
+                .about("Generate deployment artifact: Dockerfile, docker-compose.yml, or systemd unit")
+                .arg(
+                    Arg::with_name("KIND")
+                        .help("Kind of deployment artifact")
+                        .possible_values(&["docker", "compose", "systemd"])
+                        .required(true)
+                )
+                .arg(
+                    Arg::with_name("environment")
+                        .long("environment")
+                        .help("Rocket.toml environment used for the deployment{n}Default: production")
+                        .value_name("ENV")
+                        .possible_values(&["development", "staging", "production"])
+                        .required(false)
+                )
+        )
+}
+
 fn generate_new_subcommand<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("new")
         .about("Generate a new Hangar Project")
@@ -108,6 +155,20 @@ fn generate_new_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .value_name("DB_URL")
                 .required(false)
         )
+        .arg(
+            Arg::with_name("database-url-staging")
+                .long("database-url-staging")
+                .help("Url for your database in staging environment{n}Default: value of --database-url")
+                .value_name("DB_URL")
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("database-url-production")
+                .long("database-url-production")
+                .help("Url for your database in production environment{n}Default: value of --database-url")
+                .value_name("DB_URL")
+                .required(false)
+        )
         .arg(
             Arg::with_name("author")
                 .long("author")
@@ -115,6 +176,23 @@ fn generate_new_subcommand<'a, 'b>() -> App<'a, 'b> {
                 .value_name("AUTHOR")
                 .required(false)
         )
+        .arg(
+            Arg::with_name("template")
+                .long("template")
+                .help("Local directory with `hangar-template.toml` used as project template")
+                .value_name("PATH")
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("var")
+                .long("var")
+                .help("Value for a template variable, prompted if not given: `company=Acme`")
+                .value_name("NAME=VALUE")
+                .multiple(true)
+                .number_of_values(1)
+                .requires("template")
+                .required(false)
+        )
 }
 
 fn generate_run_subcommand<'a, 'b>() -> App<'a, 'b> {
@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use clap;
 use generator::generate;
 
@@ -6,12 +7,18 @@ const INVALID_NAME: &'static str = "Invalid name";
 const INVALID_DB: &'static str = "Invalid database";
 const PATH_EXIST: &'static str = "Path already exist";
 const DEFAULT_DB: &'static str = "postgresql";
+const INVALID_TEMPLATE: &'static str = "Invalid template, must be a directory";
+const INVALID_VAR: &'static str = "Invalid template variable, use NAME=VALUE";
 
 pub struct Setting {
     pub name: String,
     pub db: String,
     pub db_url: String,
+    pub db_url_staging: String,
+    pub db_url_production: String,
     pub author: String,
+    pub template: Option<PathBuf>,
+    pub template_variables: BTreeMap<String, String>,
 }
 
 pub fn execute<'a>(matches: &clap::ArgMatches<'a>) -> Result<String, String> {
@@ -19,7 +26,11 @@ pub fn execute<'a>(matches: &clap::ArgMatches<'a>) -> Result<String, String> {
         name: String::from(""),
         db: String::from("postgresql"),
         db_url: String::from(""),
+        db_url_staging: String::from(""),
+        db_url_production: String::from(""),
         author: String::from("John Doe <user@example.com>"),
+        template: None,
+        template_variables: BTreeMap::new(),
     };
 
     let path_from_name = matches.value_of("name").unwrap();
@@ -49,12 +60,44 @@ pub fn execute<'a>(matches: &clap::ArgMatches<'a>) -> Result<String, String> {
         }
     }
 
+    setting.db_url_staging = matches
+        .value_of("database-url-staging")
+        .map(String::from)
+        .unwrap_or_else(|| setting.db_url.clone());
+
+    setting.db_url_production = matches
+        .value_of("database-url-production")
+        .map(String::from)
+        .unwrap_or_else(|| setting.db_url.clone());
+
     let option_author = matches.value_of("author");
 
     if let Some(author) = option_author {
         setting.author = String::from(author);
     }
 
+    if let Some(template) = matches.value_of("template") {
+        let template_path = PathBuf::from(template);
+        if !template_path.is_dir() {
+            return Err(String::from(INVALID_TEMPLATE));
+        }
+        setting.template = Some(template_path);
+    }
+
+    if let Some(variables) = matches.values_of("var") {
+        for variable in variables {
+            let mut parts = variable.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) if !name.is_empty() => {
+                    setting
+                        .template_variables
+                        .insert(String::from(name), String::from(value));
+                }
+                _ => return Err(String::from(INVALID_VAR)),
+            }
+        }
+    }
+
     generate(&setting)
 }
 
@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+use project::Project;
+
+const PLUGIN_PREFIX: &str = "hangar-";
+
+pub const BUILTIN_COMMANDS: [(&str, &str); 5] = [
+    ("build", "Wrapper for cargo build"),
+    ("db", "Wrapper for diesel-cli"),
+    ("generate", "Generate files for an existing Hangar Project"),
+    ("new", "Generate a new Hangar Project"),
+    ("run", "Wrapper for cargo run"),
+];
+
+/// Run `hangar-<name>` from `PATH`, cargo style.
+///
+/// The plugin receives the remaining arguments, and when called inside a Hangar Project:
+/// `HANGAR_PROJECT_ROOT`, `HANGAR_PROJECT_NAME`, `HANGAR_PROJECT_VERSION`,
+/// `HANGAR_PROJECT_BIN` and `HANGAR_PROJECT_DATABASE` environment variables.
+///
+/// When the plugin fails, hangar exits with the same code.
+pub fn execute(name: &str, args: &[&str]) -> Result<String, String> {
+    let plugin_name = format!("{}{}", PLUGIN_PREFIX, name);
+    let plugin_path = match find_plugin(&plugin_name) {
+        Some(path) => path,
+        None => {
+            return Err(format!(
+                "No such command: `{}`, `{}` is not found in PATH",
+                name, plugin_name
+            ))
+        }
+    };
+
+    let mut command = Command::new(plugin_path);
+    command
+        .args(args)
+        .env("HANGAR_VERSION", env!("CARGO_PKG_VERSION"));
+    if let Ok(project) = Project::discover() {
+        command
+            .env("HANGAR_PROJECT_ROOT", &project.root)
+            .env("HANGAR_PROJECT_NAME", &project.name)
+            .env("HANGAR_PROJECT_VERSION", &project.version)
+            .env("HANGAR_PROJECT_BIN", &project.bin)
+            .env("HANGAR_PROJECT_DATABASE", &project.db);
+    }
+
+    match command.status() {
+        Ok(status) => {
+            if status.success() {
+                return Ok(format!("{:?}", status));
+            }
+            // The plugin reports its own errors, only a plugin killed by a signal has no code
+            match status.code() {
+                Some(code) => exit(code),
+                None => Err(format!("`{}` failed with {}", plugin_name, status)),
+            }
+        }
+        Err(e) => Err(format!("{:?}", e)),
+    }
+}
+
+/// Print built-in commands and `hangar-*` plugins found in `PATH`.
+pub fn list() -> Result<String, String> {
+    println!("Installed Commands:");
+    for &(name, about) in BUILTIN_COMMANDS.iter() {
+        println!("    {:<16}{}", name, about);
+    }
+    for name in find_plugins() {
+        if BUILTIN_COMMANDS.iter().all(|&(builtin, _)| builtin != name) {
+            println!("    {:<16}{}{}", name, PLUGIN_PREFIX, name);
+        }
+    }
+    Ok(String::from("Success"))
+}
+
+fn search_paths() -> Vec<PathBuf> {
+    match env::var_os("PATH") {
+        Some(paths) => env::split_paths(&paths).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn find_plugin(plugin_name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}", plugin_name, env::consts::EXE_SUFFIX);
+    search_paths()
+        .into_iter()
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+fn find_plugins() -> BTreeSet<String> {
+    let mut plugins = BTreeSet::new();
+    for dir in search_paths() {
+        let entries = match read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let file_name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(file_name) => file_name.to_string(),
+                None => continue,
+            };
+            let suffix = env::consts::EXE_SUFFIX;
+            if !file_name.starts_with(PLUGIN_PREFIX) || !file_name.ends_with(suffix)
+                || !is_executable(&path)
+            {
+                continue;
+            }
+            let name = &file_name[PLUGIN_PREFIX.len()..file_name.len() - suffix.len()];
+            if !name.is_empty() {
+                plugins.insert(name.to_string());
+            }
+        }
+    }
+    plugins
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
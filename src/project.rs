@@ -0,0 +1,136 @@
+use std::env::current_dir;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+
+use toml::Value;
+
+const NOT_A_PROJECT: &str = "Not inside a Hangar Project, cannot find `Cargo.toml` and `Rocket.toml`";
+const DEFAULT_BIN: &str = "hangar_runner";
+const DEFAULT_PORT: i64 = 8000;
+
+/// Information about an existing Hangar Project, read from its `Cargo.toml` and `Rocket.toml`.
+pub struct Project {
+    pub root: PathBuf,
+    pub name: String,
+    pub version: String,
+    pub bin: String,
+    pub db: String,
+    pub rocket_toml: Value,
+}
+
+impl Project {
+    /// Find the Hangar Project containing the current directory.
+    pub fn discover() -> Result<Project, String> {
+        let working_dir = current_dir().map_err(|e| format!("{:?}", e))?;
+        match find_root(&working_dir) {
+            Some(root) => Project::from_root(&root),
+            None => Err(String::from(NOT_A_PROJECT)),
+        }
+    }
+
+    pub fn from_root(root: &Path) -> Result<Project, String> {
+        let cargo_toml = read_toml(&root.join("Cargo.toml"))?;
+        let rocket_toml = read_toml(&root.join("Rocket.toml"))?;
+
+        let name = cargo_toml
+            .get("package")
+            .and_then(|package| package.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or("hangar")
+            .to_string();
+        let version = cargo_toml
+            .get("package")
+            .and_then(|package| package.get("version"))
+            .and_then(Value::as_str)
+            .unwrap_or("0.0.1")
+            .to_string();
+        let bin = cargo_toml
+            .get("bin")
+            .and_then(Value::as_array)
+            .and_then(|bins| bins.first())
+            .and_then(|bin| bin.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or(DEFAULT_BIN)
+            .to_string();
+        let db = detect_database(&cargo_toml);
+
+        Ok(Project {
+            root: root.to_path_buf(),
+            name,
+            version,
+            bin,
+            db,
+            rocket_toml,
+        })
+    }
+
+    /// Value of `key` in the `[environment]` table of `Rocket.toml`, falling back to `[global]`.
+    pub fn rocket_setting(&self, environment: &str, key: &str) -> Option<&Value> {
+        self.rocket_toml
+            .get(environment)
+            .and_then(|table| table.get(key))
+            .or_else(|| {
+                self.rocket_toml
+                    .get("global")
+                    .and_then(|table| table.get(key))
+            })
+    }
+
+    pub fn rocket_str(&self, environment: &str, key: &str) -> Option<String> {
+        self.rocket_setting(environment, key)
+            .and_then(Value::as_str)
+            .map(String::from)
+    }
+
+    pub fn port(&self, environment: &str) -> i64 {
+        self.rocket_setting(environment, "port")
+            .and_then(Value::as_integer)
+            .unwrap_or(DEFAULT_PORT)
+    }
+}
+
+fn find_root(start: &Path) -> Option<PathBuf> {
+    let mut path = Some(start);
+    while let Some(dir) = path {
+        if dir.join("Cargo.toml").is_file() && dir.join("Rocket.toml").is_file() {
+            return Some(dir.to_path_buf());
+        }
+        path = dir.parent();
+    }
+    None
+}
+
+fn read_toml(path: &Path) -> Result<Value, String> {
+    let mut file = match File::open(path) {
+        Err(why) => return Err(format!("Cannot open {}: {:?}", path.display(), why)),
+        Ok(file) => file,
+    };
+    let mut content = String::new();
+    if let Err(why) = file.read_to_string(&mut content) {
+        return Err(format!("Cannot read {}: {:?}", path.display(), why));
+    }
+    content
+        .parse::<Value>()
+        .map_err(|why| format!("Cannot parse {}: {}", path.display(), why))
+}
+
+// `hangar new` put the diesel backend in the default features
+fn detect_database(cargo_toml: &Value) -> String {
+    let default_features = cargo_toml
+        .get("features")
+        .and_then(|features| features.get("default"))
+        .and_then(Value::as_array);
+    let has_feature = |feature: &str| {
+        default_features
+            .map(|features| features.iter().any(|f| f.as_str() == Some(feature)))
+            .unwrap_or(false)
+    };
+    if has_feature("diesel_mysql") {
+        String::from("mysql")
+    } else if has_feature("diesel_sqlite") {
+        String::from("sqlite")
+    } else {
+        String::from("postgresql")
+    }
+}